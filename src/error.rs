@@ -0,0 +1,98 @@
+use pinocchio::program_error::ProgramError;
+
+/// Program specific errors, returned as `ProgramError::Custom(code)`.
+///
+/// The codes are part of the program interface: append new variants at the end
+/// and never reuse or renumber an existing one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum MultisigError {
+    /// The signer is not a member of the multisig
+    NotAMember = 0,
+    /// The signer is not an admin of the multisig
+    NotAnAdmin = 1,
+    /// The member is already part of the multisig
+    DuplicateMember = 2,
+    /// The threshold can't be reached with the current members
+    ThresholdUnreachable = 3,
+    /// The proposal expiry passed
+    ProposalExpired = 4,
+    /// The proposal is not open for voting
+    ProposalNotActive = 5,
+    /// An account doesn't match the PDA derived from its seeds
+    PdaMismatch = 6,
+    /// The treasury or vault doesn't belong to the multisig
+    TreasuryMismatch = 7,
+    /// The number of members or admins is invalid
+    InvalidMemberCount = 8,
+    /// The proposal expiry is in the past or beyond `max_expiry`
+    InvalidExpiry = 9,
+    /// The proposal was created before the last config change
+    ProposalStale = 10,
+    /// The proposal didn't reach the threshold
+    ProposalNotApproved = 11,
+    /// The proposal was already executed, failed or cancelled
+    ProposalFinalized = 12,
+    /// The proposal can't be closed while voting is still open
+    ProposalStillOpen = 13,
+    /// The creator account doesn't match the proposal, buffer or batch creator
+    CreatorMismatch = 14,
+    /// The transaction belongs to another multisig or proposal, or its payload changed
+    TransactionMismatch = 15,
+    /// The transaction is already linked to a proposal
+    TransactionAlreadyClaimed = 16,
+    /// The transaction index is at or below the stale transaction index
+    TransactionStale = 17,
+    /// The transaction payload is malformed or doesn't match the instruction
+    InvalidPayload = 18,
+    /// The transaction payload exceeds the buffer size
+    PayloadTooLarge = 19,
+    /// A stored instruction tries to invoke the multisig program itself
+    SelfInvocation = 20,
+    /// Unknown update type or member operation
+    InvalidUpdateType = 21,
+    /// The stale transaction index can only move forward up to the current index
+    InvalidStaleTransactionIndex = 22,
+    /// The threshold must be at least 1
+    InvalidThreshold = 23,
+    /// Only `Draft` proposals can be activated
+    ProposalNotDraft = 24,
+    /// Member weights must be greater than zero
+    InvalidWeight = 25,
+    /// The member already cast this vote
+    AlreadyVoted = 26,
+    /// The member lacks the permission required by the instruction
+    MissingPermission = 27,
+    /// The vote is neither yes, no nor abstain
+    InvalidVote = 28,
+    /// The proposal time lock hasn't elapsed yet
+    TimeLockActive = 29,
+    /// The amount exceeds what is left of the admin spending limit in this period
+    SpendingLimitExceeded = 30,
+    /// The member is not allowed to use the spending limit
+    MemberNotAllowed = 31,
+    /// The destination is not allowed by the spending limit
+    DestinationNotAllowed = 32,
+    /// The mint doesn't match the spending limit or the token account
+    MintMismatch = 33,
+    /// The decimals don't match the mint
+    DecimalsMismatch = 34,
+    /// The token account is not the treasury associated token account
+    TokenAccountMismatch = 35,
+    /// The address lookup table is invalid or doesn't hold the referenced index
+    InvalidLookupTable = 36,
+    /// The transaction can still be proposed or executed
+    TransactionNotClosable = 37,
+    /// The rent payer account doesn't match the one recorded on the transaction
+    RentPayerMismatch = 38,
+    /// The account belongs to another multisig
+    MultisigMismatch = 39,
+    /// The destination account doesn't match the destination in the instruction data
+    DestinationMismatch = 40,
+}
+
+impl From<MultisigError> for ProgramError {
+    fn from(e: MultisigError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
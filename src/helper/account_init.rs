@@ -6,7 +6,7 @@ use pinocchio::{
     sysvars::{rent::Rent, Sysvar},
     ProgramResult,
 };
-use pinocchio_system::instructions::CreateAccount;
+use pinocchio_system::instructions::{CreateAccount, Transfer};
 
 use crate::helper::utils::DataLen;
 
@@ -29,16 +29,54 @@ pub fn create_pda_account<S>(
 where
     S: StateDefinition,
 {
+    create_pda_account_with_len::<S>(payer, account, signer_seeds, rent, S::LEN)
+}
+
+/// Same as `create_pda_account` for accounts storing a variable length list after the state
+#[inline(always)]
+pub fn create_pda_account_with_len<S>(
+    payer: &AccountInfo,
+    account: &AccountInfo,
+    signer_seeds: &[Seed],
+    rent: &Rent,
+    len: usize,
+) -> Result<(), ProgramError>
+where
+    S: StateDefinition,
+{
+    if len < S::LEN {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
     let signers = [Signer::from(signer_seeds)];
 
     CreateAccount {
         from: payer,
         to: account,
-        space: S::LEN as u64,
+        space: len as u64,
         owner: &crate::ID,
-        lamports: rent.minimum_balance(S::LEN),
+        lamports: rent.minimum_balance(len),
     }
     .invoke_signed(&signers)?;
 
     Ok(())
 }
+
+/// Grows `account` to `len` bytes, `payer` tops up the rent the extra bytes need
+#[inline(always)]
+pub fn resize_account(payer: &AccountInfo, account: &AccountInfo, len: usize) -> ProgramResult {
+    let rent_diff = Rent::get()?
+        .minimum_balance(len)
+        .saturating_sub(account.lamports());
+
+    if rent_diff > 0 {
+        Transfer {
+            from: payer,
+            to: account,
+            lamports: rent_diff,
+        }
+        .invoke()?;
+    }
+
+    account.resize(len)
+}
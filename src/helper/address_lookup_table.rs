@@ -0,0 +1,46 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+use crate::error::MultisigError;
+
+pub const ADDRESS_LOOKUP_TABLE_PROGRAM_ID: Pubkey =
+    pinocchio_pubkey::pubkey!("AddressLookupTab1e1111111111111111111111111");
+
+/// Size of the lookup table metadata stored before the addresses
+const LOOKUP_TABLE_META_SIZE: usize = 56;
+/// `ProgramState::LookupTable`, the metadata starts with the `u32` state discriminant
+const LOOKUP_TABLE_DISCRIMINANT: [u8; 4] = [1, 0, 0, 0];
+/// `deactivation_slot` of a table that was not deactivated, stored right after the discriminant
+const LOOKUP_TABLE_ACTIVE: [u8; 8] = u64::MAX.to_le_bytes();
+/// Offset of the `Option<Pubkey>` tag of the table authority, 0 once the table is frozen
+const LOOKUP_TABLE_AUTHORITY_OFFSET: usize = 21;
+
+/// Addresses stored in the address lookup table `account`, 32 bytes each. Only active, frozen
+/// tables are accepted: without an authority a table can neither be extended nor deactivated
+/// and closed, so its addresses are the ones the members saw when they voted.
+pub fn lookup_table_addresses(account: &AccountInfo) -> Result<&[u8], ProgramError> {
+    if account.owner() != &ADDRESS_LOOKUP_TABLE_PROGRAM_ID {
+        return Err(MultisigError::InvalidLookupTable.into());
+    }
+
+    let data = unsafe { account.borrow_data_unchecked() };
+    if data.len() < LOOKUP_TABLE_META_SIZE
+        || data[..4] != LOOKUP_TABLE_DISCRIMINANT
+        || data[4..12] != LOOKUP_TABLE_ACTIVE
+        || data[LOOKUP_TABLE_AUTHORITY_OFFSET] != 0
+        || (data.len() - LOOKUP_TABLE_META_SIZE) % 32 != 0
+    {
+        return Err(MultisigError::InvalidLookupTable.into());
+    }
+
+    Ok(&data[LOOKUP_TABLE_META_SIZE..])
+}
+
+/// Address at `index` of a lookup table returned by `lookup_table_addresses`
+#[inline(always)]
+pub fn lookup_table_address(addresses: &[u8], index: u8) -> Result<&Pubkey, ProgramError> {
+    let start = index as usize * 32;
+    addresses
+        .get(start..start + 32)
+        .and_then(|address| address.try_into().ok())
+        .ok_or(MultisigError::InvalidLookupTable.into())
+}
@@ -3,8 +3,14 @@ pub mod utils;
 pub mod account_checks;
 pub mod account_init;
 pub mod account_close;
+pub mod address_lookup_table;
+pub mod token;
+pub mod vault;
 
 pub use utils::*;
 pub use account_checks::*;
 pub use account_init::*;
-pub use account_close::*;
\ No newline at end of file
+pub use account_close::*;
+pub use address_lookup_table::*;
+pub use token::*;
+pub use vault::*;
\ No newline at end of file
@@ -0,0 +1,94 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    cpi::slice_invoke_signed,
+    instruction::{AccountMeta, Instruction, Signer},
+    program_error::ProgramError,
+    pubkey::{self, Pubkey},
+    ProgramResult,
+};
+
+use crate::error::MultisigError;
+
+pub const TOKEN_PROGRAM_ID: Pubkey = pinocchio_pubkey::pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = pinocchio_pubkey::pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    pinocchio_pubkey::pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+/// Size of a mint without Token-2022 extensions
+const MINT_LEN: usize = 82;
+const MINT_DECIMALS_OFFSET: usize = 44;
+const MINT_INITIALIZED_OFFSET: usize = 45;
+
+/// `TransferChecked` instruction of both token programs
+const TRANSFER_CHECKED_DISCRIMINATOR: u8 = 12;
+
+#[inline(always)]
+pub fn check_token_program(token_program: &AccountInfo) -> Result<(), ProgramError> {
+    if token_program.key() != &TOKEN_PROGRAM_ID && token_program.key() != &TOKEN_2022_PROGRAM_ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+/// Decimals of an initialized `mint` owned by `token_program`
+pub fn mint_decimals(mint: &AccountInfo, token_program: &AccountInfo) -> Result<u8, ProgramError> {
+    if mint.owner() != token_program.key() {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let data = unsafe { mint.borrow_data_unchecked() };
+    if data.len() < MINT_LEN || data[MINT_INITIALIZED_OFFSET] == 0 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    Ok(data[MINT_DECIMALS_OFFSET])
+}
+
+/// Checks `token_account` is the associated token account of `owner` for `mint`
+pub fn check_associated_token_account(
+    token_account: &AccountInfo,
+    owner: &Pubkey,
+    mint: &AccountInfo,
+    token_program: &AccountInfo,
+) -> Result<(), ProgramError> {
+    let seeds = &[owner.as_slice(), token_program.key().as_slice(), mint.key().as_slice()];
+    let (associated_token_account, _) =
+        pubkey::find_program_address(seeds, &ASSOCIATED_TOKEN_PROGRAM_ID);
+
+    if associated_token_account.ne(token_account.key()) || token_account.owner() != token_program.key() {
+        return Err(MultisigError::TokenAccountMismatch.into());
+    }
+    Ok(())
+}
+
+/// CPI to `TransferChecked` of `token_program`, signed by `signers`
+pub fn transfer_checked(
+    token_program: &AccountInfo,
+    source: &AccountInfo,
+    mint: &AccountInfo,
+    destination: &AccountInfo,
+    authority: &AccountInfo,
+    amount: u64,
+    decimals: u8,
+    signers: &[Signer],
+) -> ProgramResult {
+    let metas = [
+        AccountMeta::new(source.key(), true, false),
+        AccountMeta::new(mint.key(), false, false),
+        AccountMeta::new(destination.key(), true, false),
+        AccountMeta::new(authority.key(), false, true),
+    ];
+
+    let mut data = [0u8; 10];
+    data[0] = TRANSFER_CHECKED_DISCRIMINATOR;
+    data[1..9].copy_from_slice(&amount.to_le_bytes());
+    data[9] = decimals;
+
+    let instruction = Instruction {
+        program_id: token_program.key(),
+        data: &data,
+        accounts: &metas,
+    };
+
+    slice_invoke_signed(&instruction, &[source, mint, destination, authority], signers)
+}
@@ -0,0 +1,50 @@
+use pinocchio::{
+    instruction::{Seed, Signer},
+    pubkey::{self, Pubkey},
+};
+
+use crate::state::multisig::MultisigState;
+
+pub const VAULT_SEED: &str = "vault";
+
+/// Address and bump of vault `index` of a multisig. Vault 0 is the treasury created with
+/// the multisig, the others are `["vault", multisig, index]` PDAs. Vaults are system
+/// accounts without data, so they don't have to be created before being funded.
+pub fn vault_address(multisig_key: &Pubkey, multisig: &MultisigState, index: u8) -> (Pubkey, u8) {
+    if index == 0 {
+        return (multisig.treasury, multisig.treasury_bump);
+    }
+
+    pubkey::find_program_address(
+        &[VAULT_SEED.as_bytes(), multisig_key.as_slice(), &[index]],
+        &crate::ID,
+    )
+}
+
+/// Calls `f` with the signer seeds of vault `index`
+pub fn with_vault_signer<R>(
+    multisig_key: &Pubkey,
+    index: u8,
+    bump: u8,
+    f: impl FnOnce(&[Signer]) -> R,
+) -> R {
+    let index_bytes = [index];
+    let bump_bytes = [bump];
+
+    if index == 0 {
+        let seeds = [
+            Seed::from(b"treasury"),
+            Seed::from(multisig_key),
+            Seed::from(&bump_bytes),
+        ];
+        f(&[Signer::from(&seeds)])
+    } else {
+        let seeds = [
+            Seed::from(VAULT_SEED.as_bytes()),
+            Seed::from(multisig_key),
+            Seed::from(&index_bytes),
+            Seed::from(&bump_bytes),
+        ];
+        f(&[Signer::from(&seeds)])
+    }
+}
@@ -0,0 +1,67 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use crate::error::MultisigError;
+use crate::helper::{account_checks::check_signer, account_init::StateDefinition};
+use crate::state::{
+    multisig::MultisigState,
+    proposal::{ProposalState, ProposalStatus},
+};
+
+/// Opens a `Draft` proposal for voting. Only the creator or an admin can activate it.
+///
+/// The payload hash was taken when the proposal was created, so once it is `Active`
+/// members vote on exactly that payload.
+pub fn process_activate_proposal(accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    let [signer, multisig_account, proposal_account, _remaining @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(&signer)?;
+
+    if multisig_account.owner() != &crate::ID || proposal_account.owner() != &crate::ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let multisig = MultisigState::from_account_info(multisig_account)?;
+    let mut proposal = ProposalState::from_bytes(unsafe { proposal_account.borrow_data_unchecked() })?;
+
+    ProposalState::validate_pda(
+        proposal_account.key(),
+        multisig_account.key(),
+        proposal.bump,
+        proposal.proposal_id,
+    )?;
+
+    if proposal.creator.ne(signer.key()) {
+        match MultisigState::member_index(multisig_account, signer.key()) {
+            Some(index) if index < multisig.admin_counter as usize => {}
+            _ => return Err(MultisigError::NotAnAdmin.into()),
+        }
+    }
+
+    if proposal.status != ProposalStatus::Draft {
+        return Err(MultisigError::ProposalNotDraft.into());
+    }
+
+    if proposal.is_stale(multisig) {
+        return Err(MultisigError::ProposalStale.into());
+    }
+
+    if proposal.is_expired(Clock::get()?.unix_timestamp as u64) {
+        return Err(MultisigError::ProposalExpired.into());
+    }
+
+    proposal.status = ProposalStatus::Active;
+
+    unsafe {
+        proposal_account.borrow_mut_data_unchecked()[..ProposalState::LEN]
+            .copy_from_slice(proposal.to_bytes().as_ref());
+    }
+
+    Ok(())
+}
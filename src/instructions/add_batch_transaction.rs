@@ -0,0 +1,45 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+use crate::error::MultisigError;
+use crate::helper::{account_checks::check_signer, account_init::resize_account};
+use crate::state::{BatchState, TransactionMessage};
+
+/// Appends the instruction data, a `TransactionMessage`, to a batch, creator only
+pub fn process_add_batch_transaction(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [creator, multisig_acc, batch_acc, _system_program, _rest @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(&creator)?;
+
+    let batch =
+        BatchState::load(batch_acc, multisig_acc.key()).ok_or(MultisigError::PdaMismatch)?;
+
+    if batch.creator.ne(creator.key()) {
+        return Err(MultisigError::CreatorMismatch.into());
+    }
+
+    // The proposal hash covers the transactions, the batch is frozen once claimed
+    if batch.proposal != [0u8; 32] {
+        return Err(MultisigError::TransactionAlreadyClaimed.into());
+    }
+
+    if batch.num_transactions as usize >= BatchState::MAX_TRANSACTIONS {
+        return Err(MultisigError::PayloadTooLarge.into());
+    }
+
+    TransactionMessage::parse(data)?;
+    let len = u16::try_from(data.len()).map_err(|_| MultisigError::PayloadTooLarge)?;
+
+    let start = batch_acc.data_len();
+    let new_size = start + 2 + data.len();
+
+    batch.num_transactions += 1;
+    resize_account(creator, batch_acc, new_size)?;
+
+    let transaction = unsafe { &mut batch_acc.borrow_mut_data_unchecked()[start..] };
+    transaction[..2].copy_from_slice(&len.to_le_bytes());
+    transaction[2..].copy_from_slice(data);
+
+    Ok(())
+}
@@ -5,12 +5,18 @@ use pinocchio::{
     sysvars::rent::Rent,
     ProgramResult,
 };
+use crate::error::MultisigError;
 use crate::state::{member::{MemberState, MemberRole}, multisig::MultisigState};
 use crate::helper::account_init::StateDefinition;
 use pinocchio_system::instructions::Transfer;
 
-pub(crate) fn add_member(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
-    let [payer, multisig_account, rent_acc, _remaining @ ..] = accounts else {
+pub(crate) fn add_member(
+    accounts: &[AccountInfo],
+    data: &[u8],
+    weight: u16,
+    permissions: u8,
+) -> ProgramResult {
+    let [payer, multisig_account, _proposal_account, _transaction_account, rent_acc, _remaining @ ..] = accounts else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
 
@@ -18,6 +24,10 @@ pub(crate) fn add_member(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult
         return Err(ProgramError::InvalidInstructionData);
     }
 
+    if weight == 0 {
+        return Err(MultisigError::InvalidWeight.into());
+    }
+
     let rent = Rent::from_account_info(rent_acc)?;
     let mut multisig_state = MultisigState::from_account_info(multisig_account)?;
 
@@ -37,12 +47,20 @@ pub(crate) fn add_member(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult
     for m in member_data.chunks_exact(MemberState::LEN) {
         let existing = MemberState::from_bytes(m)?;
         if existing.pubkey == new_member_pubkey {
-            return Err(ProgramError::InvalidInstructionData);
+            return Err(MultisigError::DuplicateMember.into());
         }
     }
 
+    let permissions = match permissions {
+        0 => MemberState::default_permissions(role),
+        permissions => permissions,
+    };
+
     let new_member = MemberState {
         pubkey: new_member_pubkey,
+        weight,
+        permissions,
+        _padding: [0; 5],
     };
 
     // Find insert position: after last admin (if adding admin), or at end
@@ -54,7 +72,7 @@ pub(crate) fn add_member(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult
 
     // Resize account to add new member
     let new_size = multisig_account.data_len() + MemberState::LEN;
-    let rent_diff = rent.minimum_balance(new_size) - multisig_account.lamports();
+    let rent_diff = rent.minimum_balance(new_size).saturating_sub(multisig_account.lamports());
 
     if rent_diff > 0 {
         Transfer {
@@ -96,11 +114,14 @@ pub(crate) fn add_member(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult
 
     // Update counters
     multisig_state.num_members = multisig_state.num_members.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+    multisig_state.total_weight = multisig_state.total_weight.checked_add(weight).ok_or(ProgramError::ArithmeticOverflow)?;
 
     if role == MemberRole::Admin as u8 {
         multisig_state.admin_counter = multisig_state.admin_counter.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
     }
 
+    multisig_state.check_threshold(multisig_account)?;
+
     let header_len = MultisigState::LEN;
 
     // The expression inside the block now becomes the value of `header_data`
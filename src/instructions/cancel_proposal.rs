@@ -0,0 +1,70 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+use crate::error::MultisigError;
+use crate::helper::{account_checks::check_signer, account_init::StateDefinition};
+use crate::instructions::vote::append_vote;
+use crate::state::{
+    member::Permission,
+    multisig::MultisigState,
+    proposal::{ProposalState, ProposalStatus},
+};
+
+/// Casts a cancel vote on an `Active` proposal, or on a `Succeeded` one that was not executed
+/// yet. Once `cancel_threshold` members voted to cancel, the proposal becomes `Cancelled` and
+/// its transaction can never be executed.
+///
+/// Cancel votes are stored after the yes, no and abstain votes, and their rent is refunded on close
+/// like any other vote.
+pub fn process_cancel_proposal(accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    let [member, multisig_account, proposal_account, _remaining @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(&member)?;
+
+    if multisig_account.owner() != &crate::ID || proposal_account.owner() != &crate::ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let multisig = *MultisigState::from_account_info(multisig_account)?;
+    MultisigState::check_permission(multisig_account, member.key(), Permission::Vote)?;
+
+    let mut proposal = ProposalState::from_bytes(unsafe { proposal_account.borrow_data_unchecked() })?;
+
+    ProposalState::validate_pda(
+        proposal_account.key(),
+        multisig_account.key(),
+        proposal.bump,
+        proposal.proposal_id,
+    )?;
+
+    match proposal.status {
+        ProposalStatus::Active | ProposalStatus::Succeeded => {}
+        ProposalStatus::Draft => return Err(MultisigError::ProposalNotActive.into()),
+        _ => return Err(MultisigError::ProposalFinalized.into()),
+    }
+
+    let cancel_votes_start = proposal.cast_votes() * 32;
+    let already_cancelled = unsafe { proposal_account.borrow_data_unchecked() }[ProposalState::LEN..]
+        [cancel_votes_start..]
+        .chunks_exact(32)
+        .any(|vote| vote == member.key().as_ref());
+
+    if already_cancelled {
+        return Err(MultisigError::AlreadyVoted.into());
+    }
+
+    append_vote(proposal_account, member)?;
+    proposal.cancel_votes += 1;
+
+    if proposal.cancel_votes >= multisig.cancel_threshold {
+        proposal.status = ProposalStatus::Cancelled;
+    }
+
+    unsafe {
+        proposal_account.borrow_mut_data_unchecked()[..ProposalState::LEN]
+            .copy_from_slice(proposal.to_bytes().as_ref());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    ProgramResult,
+};
+
+use crate::error::MultisigError;
+use crate::helper::{
+    account_checks::check_signer, account_close::close_account, account_init::StateDefinition,
+};
+use crate::state::{
+    multisig::MultisigState,
+    proposal::{ProposalState, ProposalStatus},
+};
+
+/// Finalizes a proposal and reclaims its rent.
+///
+/// - Reaching both `min_threshold` and `weight_threshold` moves the proposal to `Succeeded`.
+///   It is kept open until it gets executed, since execution needs the approved proposal.
+/// - An expired or stale proposal, or one that can no longer reach a threshold, becomes `Failed`.
+///   So does a `Succeeded` proposal that went stale before being executed.
+/// - The creator can withdraw a proposal nobody voted on yet, which makes it `Cancelled`.
+///
+/// `Failed`, `Cancelled` and `Executed` proposals are closed: every voter gets back the rent
+/// paid for their vote slot and the rest goes to the creator. All voters have to be passed
+/// as writable accounts after the fixed accounts.
+pub fn process_close_proposal_instruction(accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    let [closer, multisig_account, proposal_account, creator, _voters @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(&closer)?;
+
+    if multisig_account.owner() != &crate::ID || proposal_account.owner() != &crate::ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let multisig = MultisigState::from_account_info(multisig_account)?;
+    let mut proposal = ProposalState::from_bytes(unsafe { proposal_account.borrow_data_unchecked() })?;
+
+    ProposalState::validate_pda(
+        proposal_account.key(),
+        multisig_account.key(),
+        proposal.bump,
+        proposal.proposal_id,
+    )?;
+
+    if proposal.creator.ne(creator.key()) {
+        return Err(MultisigError::CreatorMismatch.into());
+    }
+
+    let current_time = Clock::get()?.unix_timestamp as u64;
+
+    if let ProposalStatus::Draft | ProposalStatus::Active = proposal.status {
+        proposal.status = if proposal.is_approved(multisig) && !proposal.is_stale(multisig) {
+            proposal.approved_at = current_time;
+            ProposalStatus::Succeeded
+        } else if proposal.is_expired(current_time)
+            || proposal.is_unreachable(multisig, multisig_account)?
+            || proposal.is_stale(multisig)
+        {
+            ProposalStatus::Failed
+        } else if closer.key() == creator.key() && proposal.cast_votes() == 0 {
+            ProposalStatus::Cancelled
+        } else {
+            // Voting is still open
+            return Err(MultisigError::ProposalStillOpen.into());
+        };
+    }
+
+    // A config change made the approved proposal unexecutable
+    if proposal.status == ProposalStatus::Succeeded && proposal.is_stale(multisig) {
+        proposal.status = ProposalStatus::Failed;
+    }
+
+    if proposal.status == ProposalStatus::Succeeded {
+        unsafe {
+            proposal_account.borrow_mut_data_unchecked()[..ProposalState::LEN]
+                .copy_from_slice(proposal.to_bytes().as_ref());
+        }
+        return Ok(());
+    }
+
+    // Refund the rent each voter paid when their vote was appended in `process_vote_instruction`
+    let rent = Rent::get()?;
+    let vote_rent = rent.minimum_balance(ProposalState::LEN + 32) - rent.minimum_balance(ProposalState::LEN);
+
+    let (_, votes) = unsafe {
+        proposal_account
+            .borrow_data_unchecked()
+            .split_at(ProposalState::LEN)
+    };
+
+    for vote in votes.chunks_exact(32) {
+        // The closer or the creator may have voted too, so look through every account
+        let voter = accounts
+            .iter()
+            .find(|voter| voter.key().as_ref() == vote)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+        let proposal_lamports = proposal_account
+            .lamports()
+            .checked_sub(vote_rent)
+            .ok_or(ProgramError::InsufficientFunds)?;
+
+        *proposal_account.try_borrow_mut_lamports()? = proposal_lamports;
+        *voter.try_borrow_mut_lamports()? += vote_rent;
+    }
+
+    close_account(proposal_account, creator)
+}
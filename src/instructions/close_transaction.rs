@@ -0,0 +1,109 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use crate::error::MultisigError;
+use crate::helper::{account_checks::check_signer, account_close::close_account};
+use crate::state::{
+    batch::BatchState,
+    multisig::MultisigState,
+    proposal::{ProposalState, ProposalStatus},
+    transaction::TransactionState,
+};
+
+/// Closes a transaction that can no longer be executed and returns its rent to the account
+/// that paid it. A transaction can be closed once it is stale, or once the proposal claiming
+/// it was executed, failed, cancelled or expired before succeeding. Batches are closed the
+/// same way, their rent goes back to the batch creator.
+///
+/// The proposal claiming the transaction has to be passed after the fixed accounts, unless
+/// the transaction is stale. Proposals are closed when they are finalized, so a closed
+/// proposal lets the transaction be closed too.
+pub fn process_close_transaction(accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    let [closer, multisig_account, transaction_account, rent_payer, remaining @ ..] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(&closer)?;
+
+    if multisig_account.owner() != &crate::ID || transaction_account.owner() != &crate::ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let multisig = MultisigState::from_account_info(multisig_account)?;
+
+    let (transaction_index, claimed_by) =
+        match BatchState::load(transaction_account, multisig_account.key()) {
+            Some(batch) => {
+                if batch.creator.ne(rent_payer.key()) {
+                    return Err(MultisigError::CreatorMismatch.into());
+                }
+                (batch.transaction_index, batch.proposal)
+            }
+            None => {
+                let transaction = TransactionState::from_account_info(transaction_account)?;
+
+                if transaction.multisig.ne(multisig_account.key()) {
+                    return Err(MultisigError::TransactionMismatch.into());
+                }
+
+                TransactionState::validate_pda(
+                    transaction_account.key(),
+                    multisig_account.key(),
+                    transaction.transaction_index,
+                    transaction.bump,
+                )?;
+
+                if transaction.rent_payer.ne(rent_payer.key()) {
+                    return Err(MultisigError::RentPayerMismatch.into());
+                }
+                (transaction.transaction_index, transaction.proposal)
+            }
+        };
+
+    let is_stale = transaction_index <= multisig.stale_transaction_index;
+
+    if !is_stale {
+        if claimed_by == [0u8; 32] {
+            return Err(MultisigError::TransactionNotClosable.into());
+        }
+
+        let [proposal_account, ..] = remaining else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if claimed_by.ne(proposal_account.key()) {
+            return Err(MultisigError::TransactionMismatch.into());
+        }
+
+        // A proposal account owned by the program but approving another transaction was
+        // closed and created again with the same seed
+        if proposal_account.owner() == &crate::ID {
+            let proposal =
+                ProposalState::from_bytes(unsafe { proposal_account.borrow_data_unchecked() })?;
+
+            if proposal.transaction.eq(transaction_account.key()) {
+                let current_time = Clock::get()?.unix_timestamp as u64;
+
+                // Proposals finalized as `Succeeded` stay executable after their expiry
+                let is_closable = match proposal.status {
+                    ProposalStatus::Executed | ProposalStatus::Failed | ProposalStatus::Cancelled => {
+                        true
+                    }
+                    ProposalStatus::Draft | ProposalStatus::Active => proposal.is_expired(current_time),
+                    ProposalStatus::Succeeded => false,
+                };
+
+                if !is_closable {
+                    return Err(MultisigError::TransactionNotClosable.into());
+                }
+            }
+        }
+    }
+
+    close_account(transaction_account, rent_payer)
+}
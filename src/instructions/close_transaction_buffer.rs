@@ -0,0 +1,38 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+use crate::error::MultisigError;
+use crate::helper::{account_checks::check_signer, account_close::close_account};
+use crate::state::TransactionBufferState;
+
+/// Closes a buffer that won't be finalized and returns its rent to the creator
+pub fn process_close_transaction_buffer(accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    let [creator, multisig_acc, buffer_acc, _rest @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(&creator)?;
+
+    if buffer_acc.owner() != &crate::ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let buffer = *TransactionBufferState::from_account_info(buffer_acc)?;
+
+    if buffer.multisig.ne(multisig_acc.key()) {
+        return Err(MultisigError::MultisigMismatch.into());
+    }
+
+    if buffer.creator.ne(creator.key()) {
+        return Err(MultisigError::CreatorMismatch.into());
+    }
+
+    TransactionBufferState::validate_pda(
+        buffer_acc.key(),
+        multisig_acc.key(),
+        creator.key(),
+        buffer.seed,
+        buffer.bump,
+    )?;
+
+    close_account(buffer_acc, creator)
+}
@@ -0,0 +1,90 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::Seed,
+    program_error::ProgramError,
+    pubkey,
+    sysvars::rent::Rent,
+    ProgramResult,
+};
+
+use crate::error::MultisigError;
+use crate::helper::{
+    account_checks::check_signer,
+    account_init::{create_pda_account, StateDefinition},
+    utils::{load_ix_data, DataLen},
+};
+use crate::state::{BatchState, MultisigState, Permission};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
+pub struct CreateBatchIxData {
+    pub vault_index: u8, // 1 byte, vault signing the transactions, 0 for the treasury
+}
+
+impl DataLen for CreateBatchIxData {
+    const LEN: usize = core::mem::size_of::<CreateBatchIxData>();
+}
+
+/// Creates an empty batch at the next transaction index of a multisig. The batch PDA is
+/// seeded by the multisig and `MultisigState.transaction_index + 1`, like transactions.
+pub fn process_create_batch(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [creator, multisig_acc, batch_acc, sysvar_rent_acc, _system_program, _rest @ ..] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(&creator)?;
+
+    if multisig_acc.owner() != &crate::ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    if !batch_acc.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    MultisigState::check_permission(multisig_acc, creator.key(), Permission::Initiate)?;
+
+    let ix_data = unsafe { load_ix_data::<CreateBatchIxData>(data)? };
+
+    let multisig = MultisigState::from_account_info(multisig_acc)?;
+
+    let transaction_index = multisig
+        .transaction_index
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    let transaction_index_bytes = transaction_index.to_le_bytes();
+
+    let seeds = &[
+        BatchState::SEED.as_bytes(),
+        multisig_acc.key().as_slice(),
+        &transaction_index_bytes,
+    ];
+    let (pda_batch, batch_bump) = pubkey::find_program_address(seeds, &crate::ID);
+
+    if pda_batch.ne(batch_acc.key()) {
+        return Err(MultisigError::PdaMismatch.into());
+    }
+
+    let bump_bytes = [batch_bump];
+    let signer_seeds = [
+        Seed::from(BatchState::SEED.as_bytes()),
+        Seed::from(multisig_acc.key().as_slice()),
+        Seed::from(&transaction_index_bytes),
+        Seed::from(&bump_bytes[..]),
+    ];
+
+    let rent = Rent::from_account_info(sysvar_rent_acc)?;
+    create_pda_account::<BatchState>(creator, batch_acc, &signer_seeds, &rent)?;
+
+    let batch = BatchState::from_account_info(batch_acc)?;
+    batch.multisig = *multisig_acc.key();
+    batch.creator = *creator.key();
+    batch.transaction_index = transaction_index;
+    batch.bump = batch_bump;
+    batch.vault_index = ix_data.vault_index;
+
+    multisig.transaction_index = transaction_index;
+
+    Ok(())
+}
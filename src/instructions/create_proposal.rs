@@ -1,19 +1,21 @@
+use crate::error::MultisigError;
 use crate::helper::{
     account_init::{create_pda_account, StateDefinition},
-    utils::{load_ix_data, DataLen},
+    utils::{hash, load_ix_data, DataLen},
     account_checks::check_signer,
 };
 use crate::state::{
     multisig::MultisigState,
-    member::MemberState,
+    member::Permission,
     proposal::{self, ProposalState, ProposalStatus},
+    transaction::TransactionState,
+    batch::BatchState,
 };
 use pinocchio::{
     account_info::AccountInfo,
     instruction::{Seed, Signer},
     program_error::ProgramError,
     pubkey,
-    pubkey::Pubkey,
     sysvars::{clock::Clock, rent::Rent, Sysvar},
     ProgramResult,
 };
@@ -30,7 +32,7 @@ impl DataLen for CreateProposalIxData {
 }
 
 pub fn process_create_proposal_instruction(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
-    let [creator, proposal_account, multisig_account, rent_sysvar_acc, clock_sysvar_acc, _remaining @ ..] = accounts
+    let [creator, proposal_account, multisig_account, transaction_account, rent_sysvar_acc, clock_sysvar_acc, _remaining @ ..] = accounts
     else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
@@ -41,41 +43,64 @@ pub fn process_create_proposal_instruction(accounts: &[AccountInfo], data: &[u8]
         return Err(ProgramError::AccountAlreadyInitialized);
     }
 
-    let rent_account = Rent::from_account_info(rent_sysvar_acc)?;
-    let ix_data = unsafe { load_ix_data::<CreateProposalIxData>(&data)? };
+    if multisig_account.owner() != &crate::ID || transaction_account.owner() != &crate::ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    // A proposal approves exactly one transaction or batch of this multisig, and a
+    // transaction or batch can only be claimed by one proposal
+    let (claimed_by, transaction_multisig, transaction_index, payload) =
+        match BatchState::load(transaction_account, multisig_account.key()) {
+            Some(batch) => {
+                if batch.num_transactions == 0 {
+                    return Err(MultisigError::InvalidPayload.into());
+                }
+                (
+                    &mut batch.proposal,
+                    batch.multisig,
+                    batch.transaction_index,
+                    BatchState::transactions(transaction_account)?,
+                )
+            }
+            None => {
+                let transaction = TransactionState::from_account_info(transaction_account)?;
+                (
+                    &mut transaction.proposal,
+                    transaction.multisig,
+                    transaction.transaction_index,
+                    TransactionState::load_payload(transaction_account)?,
+                )
+            }
+        };
+
+    if transaction_multisig.ne(multisig_account.key()) {
+        return Err(MultisigError::TransactionMismatch.into());
+    }
+
+    if *claimed_by != [0u8; 32] {
+        return Err(MultisigError::TransactionAlreadyClaimed.into());
+    }
 
     let multisig = MultisigState::from_account_info(multisig_account)?;
 
-    // Check if creator is an admin (only if there are admins)
-    if multisig.admin_counter > 0 {
-        let admin_counter = multisig.admin_counter;
+    if transaction_index <= multisig.stale_transaction_index {
+        return Err(MultisigError::TransactionStale.into());
+    }
 
-        // Get all members from the multisig account
-        let (_, member_data) = unsafe {
-            multisig_account
-                .borrow_mut_data_unchecked()
-                .split_at_mut_unchecked(MultisigState::LEN)
-        };
+    let tx_hash = hash(payload);
 
-        // Check if creator is an admin
-        let mut is_creator_admin = false;
-        for i in 0..admin_counter as usize {
-            let member_start = i * MemberState::LEN;
-            let member_end = member_start + MemberState::LEN;
-            let member_bytes = &member_data[member_start..member_end];
-            let member_pubkey = Pubkey::from(*unsafe { &*(member_bytes.as_ptr() as *const [u8; 32]) });
-
-            if member_pubkey == *creator.key() {
-                is_creator_admin = true;
-                break;
-            }
-        }
+    let rent_account = Rent::from_account_info(rent_sysvar_acc)?;
+    let ix_data = unsafe { load_ix_data::<CreateProposalIxData>(&data)? };
 
-        if !is_creator_admin {
-            return Err(ProgramError::InvalidAccountData);
-        }
+    // `expiry` is a unix timestamp and has to fall within the multisig's `max_expiry` window
+    let current_time = Clock::from_account_info(clock_sysvar_acc)?.unix_timestamp as u64;
+
+    if ix_data.expiry <= current_time || ix_data.expiry > current_time.saturating_add(multisig.max_expiry) {
+        return Err(MultisigError::InvalidExpiry.into());
     }
 
+    MultisigState::check_permission(multisig_account, creator.key(), Permission::Initiate)?;
+
     let seeds = &[
         ProposalState::SEED.as_bytes(),
         multisig_account.key().as_slice(),
@@ -84,7 +109,7 @@ pub fn process_create_proposal_instruction(accounts: &[AccountInfo], data: &[u8]
     let (pda_proposal, proposal_bump) = pubkey::find_program_address(seeds, &crate::ID);
 
     if pda_proposal.ne(proposal_account.key()) {
-        return Err(ProgramError::InvalidAccountOwner);
+        return Err(MultisigError::PdaMismatch.into());
     }
 
     let bump_bytes = [proposal_bump];
@@ -98,10 +123,26 @@ pub fn process_create_proposal_instruction(accounts: &[AccountInfo], data: &[u8]
 
     create_pda_account::<ProposalState>(&creator, &proposal_account, &signer_seeds, &rent_account)?;
 
-    let current_time = Clock::from_account_info(clock_sysvar_acc)?.unix_timestamp as u64;
+    // Proposal data is read with `ProposalState::from_bytes` everywhere, so write it in the same layout
+    let mut proposal = ProposalState::from_bytes(unsafe { proposal_account.borrow_data_unchecked() })?;
+    proposal.new(
+        ix_data.primary_seed,
+        ix_data.expiry,
+        ProposalStatus::Draft,
+        proposal_bump,
+        current_time,
+        creator.key(),
+        transaction_account.key(),
+        tx_hash,
+        transaction_index,
+    );
+
+    unsafe {
+        proposal_account.borrow_mut_data_unchecked()[..ProposalState::LEN]
+            .copy_from_slice(proposal.to_bytes().as_ref());
+    }
 
-    let proposal = ProposalState::from_account_info(&proposal_account)?;
-    proposal.new(ix_data.primary_seed, ix_data.expiry, ProposalStatus::Draft, proposal_bump, current_time);
+    *claimed_by = *proposal_account.key();
 
     Ok(())
 }
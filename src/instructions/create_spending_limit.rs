@@ -0,0 +1,152 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::Seed,
+    program_error::ProgramError,
+    pubkey::{self, Pubkey},
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    ProgramResult,
+};
+
+use crate::error::MultisigError;
+use crate::helper::{
+    account_checks::check_signer,
+    account_init::{create_pda_account_with_len, StateDefinition},
+    utils::{load_ix_data, DataLen},
+};
+use crate::instructions::MultisigInstructions;
+use crate::state::{
+    MultisigState, Permission, ProposalState, SpendingLimitPeriod, SpendingLimitState,
+    TransactionState,
+};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
+pub struct CreateSpendingLimitIxData {
+    pub amount: u64,          // 8 bytes, amount per period
+    pub mint: Pubkey,         // 32 bytes, all zeroes for native SOL
+    pub seed: u16,            // 2 bytes
+    pub period: u8,           // 1 byte, `SpendingLimitPeriod`
+    pub num_members: u8,      // 1 byte
+    pub num_destinations: u8, // 1 byte, 0 for any destination
+    pub vault_index: u8,      // 1 byte, 0 for the treasury
+    pub _padding: [u8; 2],
+}
+
+impl DataLen for CreateSpendingLimitIxData {
+    const LEN: usize = core::mem::size_of::<CreateSpendingLimitIxData>();
+}
+
+/// Creates a `SpendingLimit` PDA approved by a proposal. The fixed data is followed by the
+/// allowed members, then the allowed destinations, 32 bytes each.
+pub fn process_create_spending_limit(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [payer, multisig_account, proposal_account, transaction_account, spending_limit_account, rent_sysvar_acc, _system_program, _remaining @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(&payer)?;
+
+    if multisig_account.owner() != &crate::ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    if !spending_limit_account.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    MultisigState::check_permission(multisig_account, payer.key(), Permission::Execute)?;
+
+    if data.len() < CreateSpendingLimitIxData::LEN {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let (fixed, lists) = data.split_at(CreateSpendingLimitIxData::LEN);
+    let ix_data = unsafe { load_ix_data::<CreateSpendingLimitIxData>(fixed)? };
+
+    SpendingLimitPeriod::try_from(ix_data.period)?;
+
+    if ix_data.amount == 0 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    if ix_data.num_members == 0 {
+        return Err(MultisigError::InvalidMemberCount.into());
+    }
+
+    let num_members = ix_data.num_members as usize;
+    let num_destinations = ix_data.num_destinations as usize;
+
+    if lists.len() != (num_members + num_destinations) * 32 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    for member in lists[..num_members * 32].chunks_exact(32) {
+        let member: &Pubkey = member.try_into().map_err(|_| ProgramError::InvalidInstructionData)?;
+        MultisigState::member_index(multisig_account, member).ok_or(MultisigError::NotAMember)?;
+    }
+
+    let multisig = *MultisigState::from_account_info(multisig_account)?;
+    let mut proposal = ProposalState::load_approved(
+        proposal_account,
+        multisig_account,
+        transaction_account,
+        &multisig,
+    )?;
+
+    let transaction = TransactionState::from_account_info(transaction_account)?;
+    if !transaction.is_program_instruction(MultisigInstructions::CreateSpendingLimit as u8, data) {
+        return Err(MultisigError::InvalidPayload.into());
+    }
+
+    let seed_bytes = ix_data.seed.to_le_bytes();
+    let seeds = &[
+        SpendingLimitState::SEED.as_bytes(),
+        multisig_account.key().as_slice(),
+        &seed_bytes,
+    ];
+    let (pda_spending_limit, spending_limit_bump) = pubkey::find_program_address(seeds, &crate::ID);
+
+    if pda_spending_limit.ne(spending_limit_account.key()) {
+        return Err(MultisigError::PdaMismatch.into());
+    }
+
+    let bump_bytes = [spending_limit_bump];
+    let signer_seeds = [
+        Seed::from(SpendingLimitState::SEED.as_bytes()),
+        Seed::from(multisig_account.key().as_slice()),
+        Seed::from(&seed_bytes),
+        Seed::from(&bump_bytes[..]),
+    ];
+
+    let rent = Rent::from_account_info(rent_sysvar_acc)?;
+    create_pda_account_with_len::<SpendingLimitState>(
+        payer,
+        spending_limit_account,
+        &signer_seeds,
+        &rent,
+        SpendingLimitState::size(num_members, num_destinations),
+    )?;
+
+    let spending_limit = SpendingLimitState::from_account_info(spending_limit_account)?;
+    spending_limit.multisig = *multisig_account.key();
+    spending_limit.mint = ix_data.mint;
+    spending_limit.amount = ix_data.amount;
+    spending_limit.remaining_amount = ix_data.amount;
+    spending_limit.last_reset = Clock::get()?.unix_timestamp as u64;
+    spending_limit.seed = ix_data.seed;
+    spending_limit.period = ix_data.period;
+    spending_limit.bump = spending_limit_bump;
+    spending_limit.num_members = ix_data.num_members;
+    spending_limit.num_destinations = ix_data.num_destinations;
+    spending_limit.vault_index = ix_data.vault_index;
+
+    unsafe {
+        spending_limit_account.borrow_mut_data_unchecked()[SpendingLimitState::LEN..]
+            .copy_from_slice(lists);
+    }
+
+    proposal.mark_executed(proposal_account);
+
+    Ok(())
+}
@@ -7,34 +7,42 @@ use pinocchio::{
     sysvars::rent::Rent,
 };
 
+use crate::error::MultisigError;
 use crate::helper::account_init::StateDefinition;
 use crate::{
     state::{
+        MultisigState,
+        Permission,
+        TransactionMessage,
         TransactionState,
     },
     helper::{
         utils::{load_ix_data, DataLen},
         account_checks::check_signer,
-        account_init::create_pda_account,
+        account_init::create_pda_account_with_len,
     },
 };
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
 pub struct CreateTransactionIxData {
-    pub transaction_index: u64,  // 8 bytes
-    pub primary_seed: u16,       // 2 bytes
     pub tx_buffer: [u8; 512],    // 512 bytes
     pub buffer_size: u16,        // 2 bytes
+    pub vault_index: u8,         // 1 byte, vault signing the instructions, 0 for the treasury
+    pub _padding: u8,            // 1 byte
 }
 
 impl DataLen for CreateTransactionIxData {
     const LEN: usize = core::mem::size_of::<CreateTransactionIxData>();
 }
 
+/// Creates the next transaction of a multisig. The transaction PDA is seeded by the
+/// multisig and `MultisigState.transaction_index + 1`, so only members can create
+/// transactions and indices are assigned by the program.
 pub fn process_create_transaction(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
     let [
         payer,
+        multisig_acc,
         transaction_acc,
         sysvar_rent_acc,
         _system_program,
@@ -45,32 +53,89 @@ pub fn process_create_transaction(accounts: &[AccountInfo], data: &[u8]) -> Prog
 
     check_signer(&payer)?;
 
+    if multisig_acc.owner() != &crate::ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+
     if !transaction_acc.data_is_empty() {
         return Err(ProgramError::AccountAlreadyInitialized);
     }
 
+    MultisigState::check_permission(multisig_acc, payer.key(), Permission::Initiate)?;
+
     let rent = Rent::from_account_info(sysvar_rent_acc)?;
 
     let ix_data = unsafe { load_ix_data::<CreateTransactionIxData>(&data)? };
-    let seeds = &[TransactionState::SEED.as_bytes(), &ix_data.primary_seed.to_le_bytes()];
+
+    let payload = ix_data
+        .tx_buffer
+        .get(..ix_data.buffer_size as usize)
+        .ok_or(MultisigError::PayloadTooLarge)?;
+
+    create_transaction_account(payer, multisig_acc, transaction_acc, &rent, payload, ix_data.vault_index)
+}
+
+/// Creates the transaction PDA at `MultisigState.transaction_index + 1` holding `payload`,
+/// a `TransactionMessage`, and advances the index. Shared by `CreateTransaction` and
+/// `FinalizeTransactionBuffer`.
+pub(crate) fn create_transaction_account(
+    payer: &AccountInfo,
+    multisig_acc: &AccountInfo,
+    transaction_acc: &AccountInfo,
+    rent: &Rent,
+    payload: &[u8],
+    vault_index: u8,
+) -> ProgramResult {
+    // Malformed payloads are rejected here rather than when the transaction is executed
+    TransactionMessage::parse(payload)?;
+
+    let multisig = MultisigState::from_account_info(multisig_acc)?;
+
+    let transaction_index = multisig
+        .transaction_index
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    let transaction_index_bytes = transaction_index.to_le_bytes();
+
+    let seeds = &[
+        TransactionState::SEED.as_bytes(),
+        multisig_acc.key().as_slice(),
+        &transaction_index_bytes,
+    ];
 
     let (derived_transaction_pda, bump) = pubkey::find_program_address(seeds, &crate::ID);
 
     if derived_transaction_pda.ne(transaction_acc.key()) {
-        return Err(ProgramError::InvalidAccountOwner);
+        return Err(MultisigError::PdaMismatch.into());
     }
 
     let bump_bytes = [bump];
-    let primary_seed_bytes = ix_data.primary_seed.to_le_bytes();
     let signer_seeds = [
         Seed::from(TransactionState::SEED.as_bytes()),
-        Seed::from(&primary_seed_bytes),
+        Seed::from(multisig_acc.key().as_slice()),
+        Seed::from(&transaction_index_bytes),
         Seed::from(&bump_bytes[..]),
     ];
 
-    create_pda_account::<TransactionState>(&payer, &transaction_acc, &signer_seeds, &rent)?;
-    
-    TransactionState::initialize(transaction_acc, ix_data, bump)?;
+    create_pda_account_with_len::<TransactionState>(
+        payer,
+        transaction_acc,
+        &signer_seeds,
+        rent,
+        TransactionState::size(payload.len()),
+    )?;
+
+    TransactionState::initialize(
+        transaction_acc,
+        multisig_acc.key(),
+        transaction_index,
+        payload,
+        vault_index,
+        bump,
+        payer.key(),
+    )?;
+
+    multisig.transaction_index = transaction_index;
 
     Ok(())
 }
@@ -0,0 +1,115 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::Seed,
+    program_error::ProgramError,
+    pubkey,
+    sysvars::rent::Rent,
+    ProgramResult,
+};
+
+use crate::error::MultisigError;
+use crate::helper::{
+    account_checks::check_signer,
+    account_init::{create_pda_account_with_len, StateDefinition},
+    utils::{load_ix_data, DataLen},
+};
+use crate::state::{MultisigState, Permission, TransactionBufferState};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
+pub struct CreateTransactionBufferIxData {
+    pub final_hash: [u8; 32], // 32 bytes, SHA-256 of the complete payload
+    pub final_size: u16,      // 2 bytes, size of the complete payload
+    pub seed: u16,            // 2 bytes
+    pub vault_index: u8,      // 1 byte, vault signing the instructions, 0 for the treasury
+    pub _padding: u8,         // 1 byte
+}
+
+impl DataLen for CreateTransactionBufferIxData {
+    const LEN: usize = core::mem::size_of::<CreateTransactionBufferIxData>();
+}
+
+/// Creates a `TransactionBuffer` PDA (`["transaction_buffer", multisig, creator, seed]`)
+/// to upload a transaction payload larger than `CreateTransaction` allows. The fixed data
+/// can be followed by the first chunk of the payload.
+pub fn process_create_transaction_buffer(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [creator, multisig_acc, buffer_acc, sysvar_rent_acc, _system_program, _rest @ ..] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(&creator)?;
+
+    if multisig_acc.owner() != &crate::ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    if !buffer_acc.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    MultisigState::check_permission(multisig_acc, creator.key(), Permission::Initiate)?;
+
+    if data.len() < CreateTransactionBufferIxData::LEN {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let (fixed, chunk) = data.split_at(CreateTransactionBufferIxData::LEN);
+    let ix_data = unsafe { load_ix_data::<CreateTransactionBufferIxData>(fixed)? };
+
+    if ix_data.final_size == 0 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    if ix_data.final_size as usize > TransactionBufferState::MAX_SIZE
+        || chunk.len() > ix_data.final_size as usize
+    {
+        return Err(MultisigError::PayloadTooLarge.into());
+    }
+
+    let seed_bytes = ix_data.seed.to_le_bytes();
+    let seeds = &[
+        TransactionBufferState::SEED.as_bytes(),
+        multisig_acc.key().as_slice(),
+        creator.key().as_slice(),
+        &seed_bytes,
+    ];
+    let (pda_buffer, buffer_bump) = pubkey::find_program_address(seeds, &crate::ID);
+
+    if pda_buffer.ne(buffer_acc.key()) {
+        return Err(MultisigError::PdaMismatch.into());
+    }
+
+    let bump_bytes = [buffer_bump];
+    let signer_seeds = [
+        Seed::from(TransactionBufferState::SEED.as_bytes()),
+        Seed::from(multisig_acc.key().as_slice()),
+        Seed::from(creator.key().as_slice()),
+        Seed::from(&seed_bytes),
+        Seed::from(&bump_bytes[..]),
+    ];
+
+    let rent = Rent::from_account_info(sysvar_rent_acc)?;
+    create_pda_account_with_len::<TransactionBufferState>(
+        creator,
+        buffer_acc,
+        &signer_seeds,
+        &rent,
+        TransactionBufferState::LEN + chunk.len(),
+    )?;
+
+    let buffer = TransactionBufferState::from_account_info(buffer_acc)?;
+    buffer.multisig = *multisig_acc.key();
+    buffer.creator = *creator.key();
+    buffer.final_hash = ix_data.final_hash;
+    buffer.final_size = ix_data.final_size;
+    buffer.seed = ix_data.seed;
+    buffer.bump = buffer_bump;
+    buffer.vault_index = ix_data.vault_index;
+
+    unsafe {
+        buffer_acc.borrow_mut_data_unchecked()[TransactionBufferState::LEN..].copy_from_slice(chunk);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+use crate::error::MultisigError;
+use crate::helper::{
+    account_checks::check_signer,
+    vault::{vault_address, with_vault_signer},
+};
+use crate::instructions::execute_transaction::invoke_instructions;
+use crate::state::{BatchState, MultisigState, Permission, ProposalState};
+
+/// Executes the next transaction of an approved batch, the proposal is executed with the last one
+pub fn process_execute_batch_transaction(accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    let [executor, multisig_account, proposal_account, batch_account, vault, remaining @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(&executor)?;
+
+    if multisig_account.owner() != &crate::ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let multisig = *MultisigState::from_account_info(multisig_account)?;
+
+    MultisigState::check_permission(multisig_account, executor.key(), Permission::Execute)?;
+
+    let mut proposal = ProposalState::load_approved_batch(
+        proposal_account,
+        multisig_account,
+        batch_account,
+        &multisig,
+    )?;
+
+    let batch = BatchState::from_account_info(batch_account)?;
+
+    let (vault_key, vault_bump) =
+        vault_address(multisig_account.key(), &multisig, batch.vault_index);
+
+    if vault_key.ne(vault.key()) {
+        return Err(MultisigError::TreasuryMismatch.into());
+    }
+
+    let payload = BatchState::transaction(batch_account, batch.num_executed)?;
+
+    if batch.mark_next_executed() {
+        proposal.mark_executed(proposal_account);
+    }
+
+    with_vault_signer(multisig_account.key(), batch.vault_index, vault_bump, |signers| {
+        invoke_instructions(payload, remaining, signers)
+    })
+}
@@ -0,0 +1,146 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    cpi::slice_invoke_signed,
+    instruction::{AccountMeta, Instruction, Signer},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    ProgramResult,
+};
+
+use crate::error::MultisigError;
+use crate::helper::{
+    account_checks::check_signer,
+    address_lookup_table::{lookup_table_address, lookup_table_addresses},
+    vault::{vault_address, with_vault_signer},
+};
+use crate::state::{
+    member::Permission, multisig::MultisigState, proposal::ProposalState,
+    transaction::{TransactionMessage, TransactionState},
+};
+
+/// Invokes the stored instructions signed by the transaction vault. Every account they
+/// reference follows the fixed accounts, lookup tables and loaded addresses included.
+pub fn process_execute_transaction(accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    let [executor, multisig_account, proposal_account, transaction_account, vault, remaining @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(&executor)?;
+
+    if multisig_account.owner() != &crate::ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let multisig = *MultisigState::from_account_info(multisig_account)?;
+
+    MultisigState::check_permission(multisig_account, executor.key(), Permission::Execute)?;
+
+    let mut proposal = ProposalState::load_approved(
+        proposal_account,
+        multisig_account,
+        transaction_account,
+        &multisig,
+    )?;
+
+    proposal.mark_executed(proposal_account);
+
+    let transaction = TransactionState::from_account_info(transaction_account)?;
+    let payload = TransactionState::load_payload(transaction_account)?;
+
+    let (vault_key, vault_bump) =
+        vault_address(multisig_account.key(), &multisig, transaction.vault_index);
+
+    if vault_key.ne(vault.key()) {
+        return Err(MultisigError::TreasuryMismatch.into());
+    }
+
+    with_vault_signer(multisig_account.key(), transaction.vault_index, vault_bump, |signers| {
+        invoke_instructions(payload, remaining, signers)
+    })
+}
+
+/// Invokes every instruction of the message stored in `payload`, signed by `signers`
+pub(crate) fn invoke_instructions(
+    payload: &[u8],
+    remaining: &[AccountInfo],
+    signers: &[Signer],
+) -> ProgramResult {
+    let message = TransactionMessage::parse(payload)?;
+    let loaded_addresses = load_addresses(&message, remaining)?;
+
+    for compiled in message.instructions() {
+        let program_id = message
+            .account_key(compiled.program_id_index)
+            .ok_or(MultisigError::InvalidPayload)?;
+
+        // The multisig must never sign for itself through a vault
+        if program_id == &crate::ID {
+            return Err(MultisigError::SelfInvocation.into());
+        }
+
+        let mut metas: Vec<AccountMeta> = Vec::with_capacity(compiled.account_indexes.len());
+        let mut account_infos: Vec<&AccountInfo> = Vec::with_capacity(compiled.account_indexes.len());
+
+        for index in compiled.account_indexes {
+            let pubkey = match message.account_key(*index) {
+                Some(pubkey) => pubkey,
+                None => loaded_addresses
+                    .get(*index as usize - message.num_account_keys())
+                    .copied()
+                    .ok_or(MultisigError::InvalidPayload)?,
+            };
+
+            let account_info = remaining
+                .iter()
+                .find(|account| account.key() == pubkey)
+                .ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+            metas.push(AccountMeta::new(
+                pubkey,
+                message.is_writable(*index),
+                message.is_signer(*index),
+            ));
+            account_infos.push(account_info);
+        }
+
+        let instruction = Instruction {
+            program_id,
+            data: compiled.data,
+            accounts: &metas,
+        };
+
+        slice_invoke_signed(&instruction, &account_infos, signers)?;
+    }
+
+    Ok(())
+}
+
+/// Resolves the addresses the message loads from lookup tables, writable ones first. The
+/// lookup table accounts are looked up in `remaining`.
+fn load_addresses<'a>(
+    message: &TransactionMessage,
+    remaining: &'a [AccountInfo],
+) -> Result<Vec<&'a Pubkey>, ProgramError> {
+    let mut writable = Vec::with_capacity(message.num_loaded_writable as usize);
+    let mut readonly = Vec::with_capacity(message.num_loaded_readonly as usize);
+
+    for lookup in message.address_table_lookups() {
+        let table = remaining
+            .iter()
+            .find(|account| account.key() == lookup.account_key)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let addresses = lookup_table_addresses(table)?;
+
+        for index in lookup.writable_indexes {
+            writable.push(lookup_table_address(addresses, *index)?);
+        }
+        for index in lookup.readonly_indexes {
+            readonly.push(lookup_table_address(addresses, *index)?);
+        }
+    }
+
+    writable.append(&mut readonly);
+    Ok(writable)
+}
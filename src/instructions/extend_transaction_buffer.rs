@@ -0,0 +1,58 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+use crate::error::MultisigError;
+use crate::helper::{
+    account_checks::check_signer,
+    account_init::{resize_account, StateDefinition},
+};
+use crate::state::TransactionBufferState;
+
+/// Appends the instruction data to the payload uploaded so far, creator only
+pub fn process_extend_transaction_buffer(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [creator, multisig_acc, buffer_acc, _system_program, _rest @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(&creator)?;
+
+    if buffer_acc.owner() != &crate::ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    if data.is_empty() {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let buffer = *TransactionBufferState::from_account_info(buffer_acc)?;
+
+    if buffer.multisig.ne(multisig_acc.key()) {
+        return Err(MultisigError::MultisigMismatch.into());
+    }
+
+    if buffer.creator.ne(creator.key()) {
+        return Err(MultisigError::CreatorMismatch.into());
+    }
+
+    TransactionBufferState::validate_pda(
+        buffer_acc.key(),
+        multisig_acc.key(),
+        creator.key(),
+        buffer.seed,
+        buffer.bump,
+    )?;
+
+    let start = buffer_acc.data_len();
+    let new_size = start + data.len();
+
+    if new_size - TransactionBufferState::LEN > buffer.final_size as usize {
+        return Err(MultisigError::PayloadTooLarge.into());
+    }
+
+    resize_account(creator, buffer_acc, new_size)?;
+
+    unsafe {
+        buffer_acc.borrow_mut_data_unchecked()[start..].copy_from_slice(data);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    sysvars::rent::Rent,
+    ProgramResult,
+};
+
+use crate::error::MultisigError;
+use crate::helper::{account_checks::check_signer, account_close::close_account, utils::hash};
+use crate::instructions::create_transaction::create_transaction_account;
+use crate::state::{MultisigState, Permission, TransactionBufferState};
+
+/// Creates the next transaction of a multisig from a fully uploaded `TransactionBuffer`,
+/// like `CreateTransaction` does from its instruction data. The uploaded payload has to
+/// match the size and hash the buffer was created with. The buffer is closed and its rent
+/// returned to the creator.
+pub fn process_finalize_transaction_buffer(accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    let [creator, multisig_acc, buffer_acc, transaction_acc, sysvar_rent_acc, _system_program, _rest @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(&creator)?;
+
+    if multisig_acc.owner() != &crate::ID || buffer_acc.owner() != &crate::ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    if !transaction_acc.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    MultisigState::check_permission(multisig_acc, creator.key(), Permission::Initiate)?;
+
+    let buffer = *TransactionBufferState::from_account_info(buffer_acc)?;
+
+    if buffer.multisig.ne(multisig_acc.key()) {
+        return Err(MultisigError::MultisigMismatch.into());
+    }
+
+    if buffer.creator.ne(creator.key()) {
+        return Err(MultisigError::CreatorMismatch.into());
+    }
+
+    TransactionBufferState::validate_pda(
+        buffer_acc.key(),
+        multisig_acc.key(),
+        creator.key(),
+        buffer.seed,
+        buffer.bump,
+    )?;
+
+    let payload = TransactionBufferState::buffer(buffer_acc)?;
+
+    if payload.len() != buffer.final_size as usize || hash(payload) != buffer.final_hash {
+        return Err(MultisigError::InvalidPayload.into());
+    }
+
+    let rent = Rent::from_account_info(sysvar_rent_acc)?;
+    create_transaction_account(
+        creator,
+        multisig_acc,
+        transaction_acc,
+        &rent,
+        payload,
+        buffer.vault_index,
+    )?;
+
+    close_account(buffer_acc, creator)
+}
@@ -6,8 +6,10 @@ use pinocchio::{
     ProgramResult,
     sysvars::rent::Rent,
 };
+use pinocchio_system::instructions::CreateAccount;
 
-use crate::state::{MultisigState, MemberRole};
+use crate::error::MultisigError;
+use crate::state::{MemberState, MultisigState, MemberRole};
 use crate::helper::{
     utils::{load_ix_data, DataLen},
     account_checks::check_signer,
@@ -22,6 +24,8 @@ pub struct InitMultisigIxData {
     pub min_threshold: u8,    // 1 byte
     pub num_members: u8,      // 1 byte
     pub num_admins: u8,       // 1 byte
+    pub cancel_threshold: u8, // 1 byte, 0 for `min_threshold`
+    pub weight_threshold: u16, // 2 bytes
 }
 
 impl DataLen for InitMultisigIxData {
@@ -41,18 +45,33 @@ pub fn process_init_multisig_instruction(accounts: &[AccountInfo], data: &[u8])
 
     let rent_account = Rent::from_account_info(rent)?;
 
+    if data.len() < InitMultisigIxData::LEN {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    // Optional member weights (one `u16` per member) and permissions (one `u8` per member)
+    // follow the fixed data
+    let (data, extra) = data.split_at(InitMultisigIxData::LEN);
     let ix_data = unsafe { load_ix_data::<InitMultisigIxData>(&data)? };
 
     if ix_data.num_members < ix_data.num_admins {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(MultisigError::InvalidMemberCount.into());
     }
 
+    let num_members = ix_data.num_members as usize;
+    let (weights, permissions) = match extra.len() {
+        0 => (extra, extra),
+        len if len == num_members * 2 => (extra, &extra[len..]),
+        len if len == num_members * 3 => extra.split_at(num_members * 2),
+        _ => return Err(ProgramError::InvalidInstructionData),
+    };
+
     // Multisig Config PDA
     let seeds = &[MultisigState::SEED.as_bytes(), &ix_data.primary_seed.to_le_bytes()];
     let (pda_multisig, multisig_bump) = pubkey::find_program_address(seeds, &crate::ID);
 
     if pda_multisig.ne(multisig.key()) {
-        return Err(ProgramError::InvalidAccountOwner);
+        return Err(MultisigError::PdaMismatch.into());
     }
 
     // Treasury PDA
@@ -61,7 +80,7 @@ pub fn process_init_multisig_instruction(accounts: &[AccountInfo], data: &[u8])
     let (pda_treasury, treasury_bump) = pubkey::find_program_address(treasury_seeds, &crate::ID);
 
     if pda_treasury.ne(treasury.key()) {
-        return Err(ProgramError::InvalidAccountOwner);
+        return Err(MultisigError::PdaMismatch.into());
     }
 
     let bump_bytes = [multisig_bump];
@@ -83,7 +102,18 @@ pub fn process_init_multisig_instruction(accounts: &[AccountInfo], data: &[u8])
     );
 
     // Add all members
-    add_all_members(creator, multisig, &rent_account, multisig_account, remaining, ix_data)?;
+    add_all_members(
+        creator,
+        multisig,
+        &rent_account,
+        multisig_account,
+        remaining,
+        ix_data,
+        weights,
+        permissions,
+    )?;
+
+    multisig_account.check_threshold(multisig)?;
 
     if !treasury.data_is_empty() {
         return Err(ProgramError::AccountAlreadyInitialized);
@@ -97,7 +127,15 @@ pub fn process_init_multisig_instruction(accounts: &[AccountInfo], data: &[u8])
         Seed::from(&treasury_bump_bytes),
     ];
 
-    create_pda_account::<MultisigState>(&creator, &treasury, &treasury_signer_seeds, &rent_account)?;
+    // The treasury stays a data-less system account so it can sign system transfers
+    CreateAccount {
+        from: creator,
+        to: treasury,
+        lamports: rent_account.minimum_balance(0),
+        space: 0,
+        owner: &pinocchio_system::ID,
+    }
+    .invoke_signed(&[Signer::from(&treasury_signer_seeds)])?;
 
     Ok(())
 }
@@ -109,6 +147,8 @@ fn add_all_members(
     multisig_account: &mut MultisigState,
     remaining: &[AccountInfo],
     ix_data: &InitMultisigIxData,
+    weights: &[u8],
+    permissions: &[u8],
 ) -> ProgramResult {
     if ix_data.num_members > 0 {
         // Calculate total size needed for all members
@@ -138,12 +178,46 @@ fn add_all_members(
 
         // Add all members in order (admins first, then normal members)
         for i in 0..ix_data.num_members as usize {
-            let member = &remaining[i];
+            let member = remaining.get(i).ok_or(ProgramError::NotEnoughAccountKeys)?;
+            if remaining[..i].iter().any(|other| other.key() == member.key()) {
+                return Err(MultisigError::DuplicateMember.into());
+            }
             let member_start = i * crate::state::member::MemberState::LEN;
             let member_end = member_start + crate::state::member::MemberState::LEN;
-            
-            // Copy the pubkey directly
-            member_data[member_start..member_end].copy_from_slice(member.key());
+
+            // Every member weighs 1 unless weights were passed
+            let weight = match weights.get(i * 2..i * 2 + 2) {
+                Some(weight) => u16::from_le_bytes([weight[0], weight[1]]),
+                None => 1,
+            };
+
+            if weight == 0 {
+                return Err(MultisigError::InvalidWeight.into());
+            }
+
+            // Members without explicit permissions get the defaults of their role
+            let role = if i < ix_data.num_admins as usize {
+                MemberRole::Admin
+            } else {
+                MemberRole::Member
+            };
+            let permissions = match permissions.get(i) {
+                Some(&permissions) if permissions != 0 => permissions,
+                _ => MemberState::default_permissions(role as u8),
+            };
+
+            let member_state = MemberState {
+                pubkey: *member.key(),
+                weight,
+                permissions,
+                _padding: [0; 5],
+            };
+            member_data[member_start..member_end].copy_from_slice(&member_state.to_bytes()?);
+
+            multisig_account.total_weight = multisig_account
+                .total_weight
+                .checked_add(weight)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
         }
 
         // Update counters
@@ -1,14 +1,48 @@
+pub mod activate_proposal;
+pub mod add_batch_transaction;
 pub mod add_member;
+pub mod cancel_proposal;
+pub mod close_proposal;
+pub mod close_transaction;
+pub mod close_transaction_buffer;
+pub mod create_batch;
 pub mod create_proposal;
+pub mod create_spending_limit;
 pub mod create_transaction;
+pub mod create_transaction_buffer;
+pub mod execute_batch_transaction;
+pub mod execute_transaction;
+pub mod extend_transaction_buffer;
+pub mod finalize_transaction_buffer;
 pub mod init_multisig;
 pub mod remove_member;
+pub mod spend_from_treasury;
+pub mod transfer_tokens;
 pub mod update_members;
 pub mod update_multisig;
+pub mod use_spending_limit;
 pub mod vote;
+pub use activate_proposal::*;
+pub use add_batch_transaction::*;
+pub use cancel_proposal::*;
+pub use close_proposal::*;
+pub use close_transaction::*;
+pub use close_transaction_buffer::*;
+pub use create_batch::*;
 pub use create_proposal::*;
+pub use create_spending_limit::*;
 pub use create_transaction::*;
+pub use create_transaction_buffer::*;
+pub use execute_batch_transaction::*;
+pub use execute_transaction::*;
+pub use extend_transaction_buffer::*;
+pub use finalize_transaction_buffer::*;
 pub use init_multisig::*;
+pub use spend_from_treasury::*;
+pub use transfer_tokens::*;
+pub use update_members::*;
+pub use update_multisig::*;
+pub use use_spending_limit::*;
 pub use vote::*;
 
 use pinocchio::program_error::ProgramError;
@@ -24,6 +58,36 @@ pub enum MultisigInstructions {
     // will close if expiry achieved & votes < threshold || execute if votes >= threshold
     CloseProposal = 4, // Nanasi + Mishal + Apaar + Ghazal
     CreateTransaction = 5,
+    // CPI the stored transaction buffer signed by the treasury once the proposal passed
+    ExecuteTransaction = 6,
+    // add / remove members, gated by an approved proposal like UpdateMultisig
+    UpdateMembers = 7,
+    // Draft -> Active, voting is only allowed on active proposals
+    ActivateProposal = 8,
+    // members vote to cancel an active or approved proposal
+    CancelProposal = 9,
+    // admins withdraw SOL from the treasury within the spending limit, no proposal needed
+    SpendFromTreasury = 10,
+    // SpendingLimit PDA approved like UpdateMultisig
+    CreateSpendingLimit = 11,
+    // allowed members spend against a SpendingLimit without a vote
+    UseSpendingLimit = 12,
+    // SPL Token / Token-2022 transfer out of the treasury ATA, approved like UpdateMultisig
+    TransferTokens = 13,
+    // upload payloads larger than CreateTransaction's 512 bytes in chunks
+    CreateTransactionBuffer = 14,
+    ExtendTransactionBuffer = 15,
+    // creates the transaction from the buffer once the payload matches its hash
+    FinalizeTransactionBuffer = 16,
+    // ordered list of transactions approved by a single proposal
+    CreateBatch = 17,
+    AddBatchTransaction = 18,
+    // executes the next transaction of an approved batch, resumable
+    ExecuteBatchTransaction = 19,
+    // reclaims the rent of a transaction that can no longer be executed
+    CloseTransaction = 20,
+    // reclaims the rent of a buffer that won't be finalized
+    CloseTransactionBuffer = 21,
     //Santoshi CHAD own version
 }
 
@@ -38,6 +102,22 @@ impl TryFrom<&u8> for MultisigInstructions {
             3 => Ok(MultisigInstructions::Vote),
             4 => Ok(MultisigInstructions::CloseProposal),
             5 => Ok(MultisigInstructions::CreateTransaction),
+            6 => Ok(MultisigInstructions::ExecuteTransaction),
+            7 => Ok(MultisigInstructions::UpdateMembers),
+            8 => Ok(MultisigInstructions::ActivateProposal),
+            9 => Ok(MultisigInstructions::CancelProposal),
+            10 => Ok(MultisigInstructions::SpendFromTreasury),
+            11 => Ok(MultisigInstructions::CreateSpendingLimit),
+            12 => Ok(MultisigInstructions::UseSpendingLimit),
+            13 => Ok(MultisigInstructions::TransferTokens),
+            14 => Ok(MultisigInstructions::CreateTransactionBuffer),
+            15 => Ok(MultisigInstructions::ExtendTransactionBuffer),
+            16 => Ok(MultisigInstructions::FinalizeTransactionBuffer),
+            17 => Ok(MultisigInstructions::CreateBatch),
+            18 => Ok(MultisigInstructions::AddBatchTransaction),
+            19 => Ok(MultisigInstructions::ExecuteBatchTransaction),
+            20 => Ok(MultisigInstructions::CloseTransaction),
+            21 => Ok(MultisigInstructions::CloseTransactionBuffer),
             _ => Err(ProgramError::InvalidInstructionData),
         }
     }
@@ -4,11 +4,12 @@ use pinocchio::{
     pubkey::Pubkey,
     ProgramResult,
 };
+use crate::error::MultisigError;
 use crate::state::{member::MemberState, multisig::MultisigState};
 use crate::helper::account_init::StateDefinition;
 
 pub(crate) fn remove_member(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
-    let [payer, multisig_account, system_program, _remaining @ ..] = accounts else {
+    let [_payer, multisig_account, _proposal_account, _transaction_account, _remaining @ ..] = accounts else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
 
@@ -39,7 +40,8 @@ pub(crate) fn remove_member(accounts: &[AccountInfo], data: &[u8]) -> ProgramRes
             break;
         }
     }
-    let idx = found_idx.ok_or(ProgramError::InvalidInstructionData)?;
+    let idx = found_idx.ok_or(MultisigError::NotAMember)?;
+    let removed_weight = MemberState::from_bytes(&member_data[idx * MemberState::LEN..])?.weight;
     // Determine if it's an admin based on position
     let is_admin = idx < multisig_state.admin_counter as usize;
 
@@ -58,9 +60,8 @@ pub(crate) fn remove_member(accounts: &[AccountInfo], data: &[u8]) -> ProgramRes
                 member_data[member2_start + i] = temp;
             }
         }
-        // Left shift all normal members (after admin section)
-        let admin_section_end = multisig_state.admin_counter as usize * MemberState::LEN;
-        let normal_members_start = admin_section_end;
+        // Left shift all normal members into the freed last admin slot
+        let normal_members_start = last_admin_idx * MemberState::LEN;
         let normal_members_end = multisig_state.num_members as usize * MemberState::LEN;
 
         for i in normal_members_start..normal_members_end - MemberState::LEN {
@@ -99,6 +100,10 @@ pub(crate) fn remove_member(accounts: &[AccountInfo], data: &[u8]) -> ProgramRes
 
     // Update num_members counter
     multisig_state.num_members = multisig_state.num_members.checked_sub(1).ok_or(ProgramError::ArithmeticOverflow)?;
+    multisig_state.total_weight = multisig_state.total_weight.checked_sub(removed_weight).ok_or(ProgramError::ArithmeticOverflow)?;
+
+    // Removals that would leave the threshold unreachable are rejected, lower the threshold first
+    multisig_state.check_threshold(multisig_account)?;
 
     Ok(())
 }
@@ -0,0 +1,69 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::Transfer;
+
+use crate::error::MultisigError;
+use crate::helper::{
+    account_checks::check_signer,
+    utils::{load_ix_data, DataLen},
+};
+use crate::state::multisig::MultisigState;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
+pub struct SpendFromTreasuryIxData {
+    pub amount: u64, // Lamports sent to the recipient
+}
+
+impl DataLen for SpendFromTreasuryIxData {
+    const LEN: usize = core::mem::size_of::<SpendFromTreasuryIxData>();
+}
+
+/// Lets an admin send SOL from the treasury without a proposal, as long as the admins
+/// together stay within `spending_limit` over the current `spending_period`.
+pub fn process_spend_from_treasury(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [admin, multisig_account, treasury, recipient, _system_program, _remaining @ ..] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(&admin)?;
+
+    if multisig_account.owner() != &crate::ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let ix_data = unsafe { load_ix_data::<SpendFromTreasuryIxData>(data)? };
+
+    let multisig = MultisigState::from_account_info(multisig_account)?;
+
+    match MultisigState::member_index(multisig_account, admin.key()) {
+        Some(index) if index < multisig.admin_counter as usize => {}
+        _ => return Err(MultisigError::NotAnAdmin.into()),
+    }
+
+    if multisig.treasury.ne(treasury.key()) {
+        return Err(MultisigError::TreasuryMismatch.into());
+    }
+
+    multisig.spend(ix_data.amount, Clock::get()?.unix_timestamp as u64)?;
+
+    let treasury_bump = [multisig.treasury_bump];
+    let treasury_seeds = [
+        Seed::from(b"treasury"),
+        Seed::from(multisig_account.key()),
+        Seed::from(&treasury_bump),
+    ];
+
+    Transfer {
+        from: treasury,
+        to: recipient,
+        lamports: ix_data.amount,
+    }
+    .invoke_signed(&[Signer::from(&treasury_seeds)])
+}
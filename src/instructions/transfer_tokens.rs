@@ -0,0 +1,101 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    ProgramResult,
+};
+
+use crate::error::MultisigError;
+use crate::helper::{
+    account_checks::check_signer,
+    token::{check_associated_token_account, check_token_program, mint_decimals, transfer_checked},
+    utils::{load_ix_data, DataLen},
+    vault::{vault_address, with_vault_signer},
+};
+use crate::instructions::MultisigInstructions;
+use crate::state::{MultisigState, Permission, ProposalState, TransactionState};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
+pub struct TransferTokensIxData {
+    pub amount: u64,         // 8 bytes
+    pub mint: Pubkey,        // 32 bytes
+    pub destination: Pubkey, // 32 bytes, destination token account
+    pub decimals: u8,        // 1 byte, has to match the mint
+    pub vault_index: u8,     // 1 byte, vault holding the tokens, 0 for the treasury
+    pub _padding: [u8; 6],
+}
+
+impl DataLen for TransferTokensIxData {
+    const LEN: usize = core::mem::size_of::<TransferTokensIxData>();
+}
+
+/// Transfers tokens out of the associated token account of a vault, approved by a proposal
+pub fn process_transfer_tokens(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [payer, multisig_account, proposal_account, transaction_account, vault, source, mint, destination, token_program, _remaining @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(&payer)?;
+
+    if multisig_account.owner() != &crate::ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    MultisigState::check_permission(multisig_account, payer.key(), Permission::Execute)?;
+
+    let ix_data = unsafe { load_ix_data::<TransferTokensIxData>(data)? };
+
+    let multisig = *MultisigState::from_account_info(multisig_account)?;
+
+    let (vault_key, vault_bump) = vault_address(multisig_account.key(), &multisig, ix_data.vault_index);
+
+    if vault_key.ne(vault.key()) {
+        return Err(MultisigError::TreasuryMismatch.into());
+    }
+
+    let mut proposal = ProposalState::load_approved(
+        proposal_account,
+        multisig_account,
+        transaction_account,
+        &multisig,
+    )?;
+
+    let transaction = TransactionState::from_account_info(transaction_account)?;
+    if !transaction.is_program_instruction(MultisigInstructions::TransferTokens as u8, data) {
+        return Err(MultisigError::InvalidPayload.into());
+    }
+
+    if ix_data.mint.ne(mint.key()) {
+        return Err(MultisigError::MintMismatch.into());
+    }
+
+    if ix_data.destination.ne(destination.key()) {
+        return Err(MultisigError::DestinationMismatch.into());
+    }
+
+    check_token_program(token_program)?;
+
+    if mint_decimals(mint, token_program)? != ix_data.decimals {
+        return Err(MultisigError::DecimalsMismatch.into());
+    }
+
+    check_associated_token_account(source, vault.key(), mint, token_program)?;
+
+    proposal.mark_executed(proposal_account);
+
+    with_vault_signer(multisig_account.key(), ix_data.vault_index, vault_bump, |signers| {
+        transfer_checked(
+            token_program,
+            source,
+            mint,
+            destination,
+            vault,
+            ix_data.amount,
+            ix_data.decimals,
+            signers,
+        )
+    })
+}
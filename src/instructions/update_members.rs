@@ -4,31 +4,77 @@ use pinocchio::{
     ProgramResult,
 };
 
-use crate::helper::utils::{load_ix_data, DataLen};
+use crate::error::MultisigError;
+use crate::helper::{
+    account_checks::check_signer,
+    utils::{load_ix_data, DataLen},
+};
+use crate::instructions::MultisigInstructions;
+use crate::state::{MultisigState, Permission, ProposalState, TransactionState};
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
 pub struct UpdateMemberIxData {
     pub operation: u8, // 1 for add, 2 for remove
     pub member_data: [u8; 33], // 32 bytes pubkey + 1 byte role (for add) or just 32 bytes pubkey (for remove)
+    pub weight: [u8; 2], // u16 LE voting weight of the added member, ignored on remove
+    pub permissions: u8, // Permission flags of the added member, 0 for the defaults of its role
 }
 
 impl DataLen for UpdateMemberIxData {
     const LEN: usize = core::mem::size_of::<UpdateMemberIxData>();
 }
 
+/// Adds or removes a member, approved by a proposal
 pub(crate) fn process_update_member(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [payer, multisig_account, proposal_account, transaction_account, _remaining @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(&payer)?;
+
+    if multisig_account.owner() != &crate::ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    MultisigState::check_permission(multisig_account, payer.key(), Permission::ManageMembers)?;
+
     let ix_data = unsafe { load_ix_data::<UpdateMemberIxData>(data)? };
 
+    let multisig = *MultisigState::from_account_info(multisig_account)?;
+    let mut proposal = ProposalState::load_approved(
+        proposal_account,
+        multisig_account,
+        transaction_account,
+        &multisig,
+    )?;
+
+    let transaction = TransactionState::from_account_info(transaction_account)?;
+    if !transaction.is_program_instruction(MultisigInstructions::UpdateMembers as u8, data) {
+        return Err(MultisigError::InvalidPayload.into());
+    }
+
     match ix_data.operation {
         1 => {
             // Add member - pass the member_data as the data parameter
-            super::add_member::add_member(accounts, &ix_data.member_data)
+            super::add_member::add_member(
+                accounts,
+                &ix_data.member_data,
+                u16::from_le_bytes(ix_data.weight),
+                ix_data.permissions,
+            )
         }
         2 => {
             // Remove member - pass only the first 32 bytes (pubkey) as the data parameter
             super::remove_member::remove_member(accounts, &ix_data.member_data[..32])
         }
-        _ => Err(ProgramError::InvalidInstructionData),
-    }
+        _ => return Err(MultisigError::InvalidUpdateType.into()),
+    }?;
+
+    // Votes cast under the old member set must not approve anything anymore
+    MultisigState::from_account_info(multisig_account)?.invalidate_transactions();
+
+    proposal.mark_executed(proposal_account);
+
+    Ok(())
 }
@@ -7,7 +7,9 @@ use pinocchio::{
     sysvars::rent::Rent,
 };
 
-use crate::state::MultisigState;
+use crate::error::MultisigError;
+use crate::instructions::MultisigInstructions;
+use crate::state::{MultisigState, Permission, ProposalState, TransactionState};
 use crate::helper::{
     utils::{load_ix_data, DataLen},
     account_checks::check_signer,
@@ -17,34 +19,65 @@ use crate::helper::{
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
 pub struct UpdateMultisigIxData {
-    pub value: u64, // For spending limit and stale transaction index
-    pub update_type: u8, // 1 for update threshold, 2 for update spending limit, 3 for stale transaction index    
-    pub threshold: u8, // For threshold updates
+    pub value: u64, // For spending limit, stale transaction index, weight threshold, time lock and spending period
+    pub update_type: u8, // 1 for update threshold, 2 for update spending limit, 3 for stale transaction index, 4 for weight threshold, 5 for cancel threshold, 6 for time lock, 7 for spending period
+    pub threshold: u8, // For threshold and cancel threshold updates
 }
 
 impl DataLen for UpdateMultisigIxData {
     const LEN: usize = core::mem::size_of::<UpdateMultisigIxData>();
 }
 
+/// Applies a config change, approved by a proposal
 pub(crate) fn process_update_multisig(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
-    let [payer, multisig, _remaining @ ..] = accounts else {
+    let [payer, multisig, proposal_account, transaction_account, _remaining @ ..] = accounts else {
         return Err(ProgramError::NotEnoughAccountKeys)
     };
 
-    if !payer.is_signer() {
-        return Err(ProgramError::MissingRequiredSignature);
+    check_signer(&payer)?;
+
+    if multisig.owner() != &crate::ID {
+        return Err(ProgramError::IllegalOwner);
     }
     
+    MultisigState::check_permission(multisig, payer.key(), Permission::Execute)?;
+
     let ix_data = unsafe { load_ix_data::<UpdateMultisigIxData>(data)? };
 
     let mut multisig_state = MultisigState::from_account_info(multisig)?;
 
+    let mut proposal = ProposalState::load_approved(
+        proposal_account,
+        multisig,
+        transaction_account,
+        multisig_state,
+    )?;
+
+    let transaction = TransactionState::from_account_info(transaction_account)?;
+    if !transaction.is_program_instruction(MultisigInstructions::UpdateMultisig as u8, data) {
+        return Err(MultisigError::InvalidPayload.into());
+    }
+
     match ix_data.update_type {
-        1 => multisig_state.update_threshold(ix_data.threshold),
+        1 => multisig_state.update_threshold(multisig, ix_data.threshold)?,
         2 => multisig_state.update_spending_limit(ix_data.value),
-        3 => multisig_state.update_stale_transaction_index(ix_data.value),
-        _ => return Err(ProgramError::InvalidInstructionData),
+        3 => multisig_state.update_stale_transaction_index(ix_data.value)?,
+        4 => {
+            let weight_threshold =
+                u16::try_from(ix_data.value).map_err(|_| MultisigError::ThresholdUnreachable)?;
+            multisig_state.update_weight_threshold(multisig, weight_threshold)?
+        }
+        5 => multisig_state.update_cancel_threshold(multisig, ix_data.threshold)?,
+        6 => {
+            let time_lock =
+                u32::try_from(ix_data.value).map_err(|_| ProgramError::InvalidInstructionData)?;
+            multisig_state.update_time_lock(time_lock)
+        }
+        7 => multisig_state.update_spending_period(ix_data.value)?,
+        _ => return Err(MultisigError::InvalidUpdateType.into()),
     }
 
+    proposal.mark_executed(proposal_account);
+
     Ok(())
-}
\ No newline at end of file
+}
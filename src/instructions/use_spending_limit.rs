@@ -0,0 +1,103 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::Transfer;
+
+use crate::error::MultisigError;
+use crate::helper::{
+    account_checks::check_signer,
+    token::{check_associated_token_account, check_token_program, mint_decimals, transfer_checked},
+    utils::{load_ix_data, DataLen},
+    vault::{vault_address, with_vault_signer},
+};
+use crate::state::{MultisigState, SpendingLimitState};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
+pub struct UseSpendingLimitIxData {
+    pub amount: u64, // Lamports or base token units sent to the destination
+}
+
+impl DataLen for UseSpendingLimitIxData {
+    const LEN: usize = core::mem::size_of::<UseSpendingLimitIxData>();
+}
+
+/// Lets a member allowed by a `SpendingLimit` send funds from its vault without a vote,
+/// within the amount left in the current period.
+///
+/// For native SOL `program` is the system program. For tokens it is the Token or Token-2022
+/// program, followed by the vault associated token account and the mint, and
+/// `destination` is the destination token account.
+pub fn process_use_spending_limit(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [member, multisig_account, spending_limit_account, vault, destination, program, remaining @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(&member)?;
+
+    if multisig_account.owner() != &crate::ID || spending_limit_account.owner() != &crate::ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let ix_data = unsafe { load_ix_data::<UseSpendingLimitIxData>(data)? };
+
+    let multisig = MultisigState::from_account_info(multisig_account)?;
+    let spending_limit = SpendingLimitState::from_account_info(spending_limit_account)?;
+
+    if spending_limit.multisig.ne(multisig_account.key()) {
+        return Err(MultisigError::MultisigMismatch.into());
+    }
+
+    SpendingLimitState::validate_pda(
+        spending_limit_account.key(),
+        multisig_account.key(),
+        spending_limit.seed,
+        spending_limit.bump,
+    )?;
+
+    // Removed members lose access to the spending limits listing them
+    MultisigState::member_index(multisig_account, member.key()).ok_or(MultisigError::NotAMember)?;
+    spending_limit.check_member(spending_limit_account, member.key())?;
+    spending_limit.check_destination(spending_limit_account, destination.key())?;
+
+    let (vault_key, vault_bump) =
+        vault_address(multisig_account.key(), multisig, spending_limit.vault_index);
+
+    if vault_key.ne(vault.key()) {
+        return Err(MultisigError::TreasuryMismatch.into());
+    }
+
+    spending_limit.spend(ix_data.amount, Clock::get()?.unix_timestamp as u64)?;
+
+    if spending_limit.mint == [0u8; 32] {
+        return with_vault_signer(multisig_account.key(), spending_limit.vault_index, vault_bump, |signers| {
+            Transfer {
+                from: vault,
+                to: destination,
+                lamports: ix_data.amount,
+            }
+            .invoke_signed(signers)
+        });
+    }
+
+    let [source, mint, _remaining @ ..] = remaining else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if spending_limit.mint.ne(mint.key()) {
+        return Err(MultisigError::MintMismatch.into());
+    }
+
+    check_token_program(program)?;
+    check_associated_token_account(source, vault.key(), mint, program)?;
+    let decimals = mint_decimals(mint, program)?;
+
+    with_vault_signer(multisig_account.key(), spending_limit.vault_index, vault_bump, |signers| {
+        transfer_checked(program, source, mint, destination, vault, ix_data.amount, decimals, signers)
+    })
+}
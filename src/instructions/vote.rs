@@ -1,12 +1,19 @@
-use crate::helper::account_init::StateDefinition;
-use crate::state::{member::MemberState, multisig::MultisigState, proposal::ProposalState};
+use crate::error::MultisigError;
+use crate::helper::{
+    account_checks::check_signer,
+    account_init::{resize_account, StateDefinition},
+};
+use crate::state::{
+    member::Permission,
+    multisig::MultisigState,
+    proposal::{ProposalState, ProposalStatus},
+};
 use pinocchio::{
     account_info::AccountInfo,
     program_error::ProgramError,
-    sysvars::{rent::Rent, Sysvar},
+    sysvars::{clock::Clock, Sysvar},
     ProgramResult,
 };
-use pinocchio_system::instructions::Transfer;
 
 use crate::helper::utils::DataLen;
 
@@ -18,6 +25,39 @@ pub struct VoteIxData {
     pub vote: u8,
 }
 
+/// Choices accepted in `VoteIxData.vote`, the discriminants are the wire values
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum VoteChoice {
+    No = 0,
+    Yes = 1,
+    Abstain = 2,
+}
+
+impl VoteChoice {
+    /// Position of the section holding the votes of this choice in the vote list
+    pub fn section(self) -> usize {
+        match self {
+            VoteChoice::Yes => 0,
+            VoteChoice::No => 1,
+            VoteChoice::Abstain => 2,
+        }
+    }
+}
+
+impl TryFrom<u8> for VoteChoice {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(VoteChoice::No),
+            1 => Ok(VoteChoice::Yes),
+            2 => Ok(VoteChoice::Abstain),
+            _ => Err(MultisigError::InvalidVote.into()),
+        }
+    }
+}
+
 impl DataLen for VoteIxData {
     const LEN: usize = core::mem::size_of::<VoteIxData>();
 }
@@ -47,30 +87,17 @@ pub fn process_vote_instruction(accounts: &[AccountInfo], data: &[u8]) -> Progra
     let [voter, multisig_account, proposal_account, _remaining @ ..] = accounts else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
+    check_signer(&voter)?;
+
     let ix_data = VoteIxData::from_bytes(data)?;
 
-    if proposal_account.owner() != &crate::ID {
+    if proposal_account.owner() != &crate::ID || multisig_account.owner() != &crate::ID {
         return Err(ProgramError::IllegalOwner);
     }
 
-    let (_, members) = unsafe {
-        multisig_account
-            .borrow_mut_data_unchecked()
-            .split_at_mut_unchecked(MultisigState::LEN)
-    };
+    let multisig = *MultisigState::from_account_info(multisig_account)?;
 
-    let mut member_exists: Option<MemberState> = None;
-    for (_, m) in members.chunks_exact(MemberState::LEN).enumerate() {
-        let member = MemberState::from_bytes(m)?;
-        if member.pubkey == *voter.key() {
-            member_exists = Some(member);
-            break;
-        }
-    }
-
-    if member_exists.is_none() {
-        return Err(ProgramError::InvalidInstructionData);
-    }
+    let member = MultisigState::check_permission(multisig_account, voter.key(), Permission::Vote)?;
 
     let (proposal, votes) = unsafe {
         proposal_account
@@ -80,118 +107,146 @@ pub fn process_vote_instruction(accounts: &[AccountInfo], data: &[u8]) -> Progra
 
     let mut proposal = ProposalState::from_bytes(proposal)?;
 
-    let mut voted = false;
-    let mut vote_index = 0;
-    for (i, vote) in votes.chunks_exact(32).enumerate() {
-        if let Some(member) = member_exists {
-            if vote == member.pubkey.as_ref() {
-                voted = true;
-                vote_index = i;
-                break;
-            }
-        }
+    ProposalState::validate_pda(
+        proposal_account.key(),
+        multisig_account.key(),
+        proposal.bump,
+        proposal.proposal_id,
+    )?;
+
+    if proposal.status != ProposalStatus::Active {
+        return Err(MultisigError::ProposalNotActive.into());
     }
 
-    if voted {
-        // Swap and update the vote count
-        if ix_data.vote == 1 {
-            // replace the last yes vote with current vote and reduce the yes vote count and increase the no vote count
-            let vote_start = vote_index * 32;
-            let swap_start = (proposal.yes_votes as usize) * 32;
-
-            if vote_start != swap_start {
-                // Use temporary variable to avoid borrowing issues
-                let mut temp_vote = [0u8; 32];
-                temp_vote.copy_from_slice(&votes[vote_start..vote_start + 32]);
-                let swap_data = votes[swap_start..swap_start + 32].to_vec();
-                votes[vote_start..vote_start + 32].copy_from_slice(&swap_data);
-                votes[swap_start..swap_start + 32].copy_from_slice(&temp_vote);
-            }
+    // The member set or threshold changed after this proposal was created
+    if proposal.is_stale(&multisig) {
+        return Err(MultisigError::ProposalStale.into());
+    }
 
-            proposal.yes_votes += 1;
-            proposal.no_votes -= 1;
-        } else {
-            // replace the last no vote with current vote and increase the yes vote count and reduce the no vote count
-            let vote_start = vote_index * 32;
-            let swap_start = (proposal.no_votes + proposal.yes_votes - 1) as usize * 32;
-
-            if vote_start != swap_start {
-                // Use temporary variable to avoid borrowing issues
-                let mut temp_vote = [0u8; 32];
-                temp_vote.copy_from_slice(&votes[vote_start..vote_start + 32]);
-                let swap_data = votes[swap_start..swap_start + 32].to_vec();
-                votes[vote_start..vote_start + 32].copy_from_slice(&swap_data);
-                votes[swap_start..swap_start + 32].copy_from_slice(&temp_vote);
-            }
+    // Expired proposals are moved to `Failed` by CloseProposal
+    let current_time = Clock::get()?.unix_timestamp as u64;
 
-            proposal.yes_votes -= 1;
-            proposal.no_votes += 1;
-        }
+    if proposal.is_expired(current_time) {
+        return Err(MultisigError::ProposalExpired.into());
+    }
 
-        unsafe {
-            proposal_account.borrow_mut_data_unchecked()[..ProposalState::LEN]
-                .copy_from_slice(proposal.to_bytes().as_ref());
-            proposal_account.borrow_mut_data_unchecked()[ProposalState::LEN..]
-                .copy_from_slice(&votes);
-        }
-    } else {
-        // Increase the size of the account to add new vote
-        let new_size = proposal_account.data_len() + 32;
-        let rent_diff = Rent::get()?.minimum_balance(new_size) - proposal_account.lamports();
-
-        if rent_diff > 0 {
-            Transfer {
-                from: voter,
-                to: proposal_account,
-                lamports: rent_diff,
-            }
-            .invoke()?;
-        }
+    let choice = VoteChoice::try_from(ix_data.vote)?;
+
+    // Votes are stored in one section per choice (yes, no, abstain), followed by the cancel votes
+    let mut counts = [proposal.yes_votes, proposal.no_votes, proposal.abstain_votes];
+    let mut weights = [proposal.yes_weight, proposal.no_weight, proposal.abstain_weight];
+    let cast_votes = proposal.cast_votes();
+
+    match votes[..cast_votes * 32]
+        .chunks_exact(32)
+        .position(|vote| vote == member.pubkey.as_ref())
+    {
+        Some(vote_index) => {
+            let previous = section_of(&counts, vote_index);
 
-        proposal_account.resize(new_size)?;
-        let (new_proposal, new_votes) = unsafe {
-            proposal_account
-                .borrow_mut_data_unchecked()
-                .split_at_mut_unchecked(ProposalState::LEN)
-        };
-
-        let mut new_proposal_data = ProposalState::from_bytes(new_proposal)?;
-
-        if let Some(member) = member_exists {
-            let last_vote_start = (proposal.yes_votes + proposal.no_votes) as usize * 32;
-
-            new_votes[last_vote_start..last_vote_start + 32]
-                .copy_from_slice(member.pubkey.as_ref());
-
-            if ix_data.vote == 1 {
-                if proposal.no_votes > 0 {
-                    let no_start = proposal.no_votes as usize * 32;
-                    // Swap the new vote (at the end) with the first no vote
-                    if last_vote_start != no_start {
-                        // Use temporary variables to avoid borrowing issues
-                        let mut temp_vote = [0u8; 32];
-                        temp_vote.copy_from_slice(&new_votes[no_start..no_start + 32]);
-                        let new_vote_data =
-                            new_votes[last_vote_start..last_vote_start + 32].to_vec();
-                        new_votes[no_start..no_start + 32].copy_from_slice(&new_vote_data);
-                        new_votes[last_vote_start..last_vote_start + 32]
-                            .copy_from_slice(&temp_vote);
-                    }
-                }
-                new_proposal_data.yes_votes += 1;
-            } else {
-                // Add the new no vote
-                new_proposal_data.no_votes += 1;
+            if previous == choice.section() {
+                return Err(MultisigError::AlreadyVoted.into());
             }
-        }
 
-        unsafe {
-            proposal_account.borrow_mut_data_unchecked()[..ProposalState::LEN]
-                .copy_from_slice(new_proposal_data.to_bytes().as_ref());
-            proposal_account.borrow_mut_data_unchecked()[ProposalState::LEN..]
-                .copy_from_slice(&new_votes);
+            move_vote(votes, &mut counts, vote_index, previous, choice.section());
+            weights[previous] -= member.weight;
+        }
+        None => {
+            let votes = append_vote(proposal_account, voter)?;
+
+            // Move the first cancel vote to the end, the freed slot becomes the last abstain vote
+            swap_votes(votes, votes.len() / 32 - 1, cast_votes);
+            counts[VoteChoice::Abstain.section()] += 1;
+
+            move_vote(
+                votes,
+                &mut counts,
+                cast_votes,
+                VoteChoice::Abstain.section(),
+                choice.section(),
+            );
         }
     }
 
+    weights[choice.section()] += member.weight;
+
+    [proposal.yes_votes, proposal.no_votes, proposal.abstain_votes] = counts;
+    [proposal.yes_weight, proposal.no_weight, proposal.abstain_weight] = weights;
+
+    // Settle the proposal as soon as the outcome is decided, so clients don't have to poll
+    if proposal.is_approved(&multisig) {
+        proposal.approve(current_time);
+    } else if proposal.is_unreachable(&multisig, multisig_account)? {
+        proposal.status = ProposalStatus::Failed;
+    }
+
+    unsafe {
+        proposal_account.borrow_mut_data_unchecked()[..ProposalState::LEN]
+            .copy_from_slice(proposal.to_bytes().as_ref());
+    }
+
     Ok(())
 }
+
+/// Grows the proposal by one vote slot paid by `voter`, writes the voter pubkey in it
+/// and returns the vote list
+pub(crate) fn append_vote<'a>(
+    proposal_account: &'a AccountInfo,
+    voter: &AccountInfo,
+) -> Result<&'a mut [u8], ProgramError> {
+    let new_size = proposal_account.data_len() + 32;
+    resize_account(voter, proposal_account, new_size)?;
+
+    let votes = unsafe { &mut proposal_account.borrow_mut_data_unchecked()[ProposalState::LEN..] };
+    let last_vote = votes.len() - 32;
+    votes[last_vote..].copy_from_slice(voter.key());
+
+    Ok(votes)
+}
+
+/// Section of the vote list holding the vote at `index`
+fn section_of(counts: &[u8; 3], index: usize) -> usize {
+    let mut end = 0;
+    for (section, count) in counts.iter().enumerate() {
+        end += *count as usize;
+        if index < end {
+            return section;
+        }
+    }
+    counts.len()
+}
+
+/// Moves the vote at `index` from section `from` to section `to`, one section boundary at
+/// a time: the vote is swapped with the slot at the boundary, which then changes section
+fn move_vote(votes: &mut [u8], counts: &mut [u8; 3], mut index: usize, from: usize, to: usize) {
+    let mut section = from;
+
+    while section > to {
+        let first: usize = counts[..section].iter().map(|count| *count as usize).sum();
+        swap_votes(votes, index, first);
+        index = first;
+        counts[section] -= 1;
+        section -= 1;
+        counts[section] += 1;
+    }
+
+    while section < to {
+        let last: usize = counts[..=section].iter().map(|count| *count as usize).sum::<usize>() - 1;
+        swap_votes(votes, index, last);
+        index = last;
+        counts[section] -= 1;
+        section += 1;
+        counts[section] += 1;
+    }
+}
+
+/// Swaps the voter pubkeys stored at positions `a` and `b` of the vote list
+pub(crate) fn swap_votes(votes: &mut [u8], a: usize, b: usize) {
+    if a == b {
+        return;
+    }
+
+    let (low, high) = (a.min(b) * 32, a.max(b) * 32);
+    let (head, tail) = votes.split_at_mut(high);
+    head[low..low + 32].swap_with_slice(&mut tail[..32]);
+}
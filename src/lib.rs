@@ -9,6 +9,7 @@ use pinocchio::{
     ProgramResult,
 };
 
+pub mod error;
 pub mod helper;
 pub mod instructions;
 pub mod state;
@@ -32,6 +33,7 @@ pub fn process_instruction(
         MultisigInstructions::InitMultisig => {
             instructions::process_init_multisig_instruction(accounts, data)?
         }
+        MultisigInstructions::UpdateMultisig => instructions::process_update_multisig(accounts, data)?,
         MultisigInstructions::CreateTransaction => {
             instructions::process_create_transaction(accounts, data)?
         }
@@ -39,7 +41,53 @@ pub fn process_instruction(
             instructions::process_create_proposal_instruction(accounts, data)?
         }
         MultisigInstructions::Vote => instructions::process_vote_instruction(accounts, data)?,
-        _ => todo!(),
+        MultisigInstructions::CloseProposal => {
+            instructions::process_close_proposal_instruction(accounts, data)?
+        }
+        MultisigInstructions::ExecuteTransaction => {
+            instructions::process_execute_transaction(accounts, data)?
+        }
+        MultisigInstructions::UpdateMembers => instructions::process_update_member(accounts, data)?,
+        MultisigInstructions::ActivateProposal => {
+            instructions::process_activate_proposal(accounts, data)?
+        }
+        MultisigInstructions::CancelProposal => {
+            instructions::process_cancel_proposal(accounts, data)?
+        }
+        MultisigInstructions::SpendFromTreasury => {
+            instructions::process_spend_from_treasury(accounts, data)?
+        }
+        MultisigInstructions::CreateSpendingLimit => {
+            instructions::process_create_spending_limit(accounts, data)?
+        }
+        MultisigInstructions::UseSpendingLimit => {
+            instructions::process_use_spending_limit(accounts, data)?
+        }
+        MultisigInstructions::TransferTokens => {
+            instructions::process_transfer_tokens(accounts, data)?
+        }
+        MultisigInstructions::CreateTransactionBuffer => {
+            instructions::process_create_transaction_buffer(accounts, data)?
+        }
+        MultisigInstructions::ExtendTransactionBuffer => {
+            instructions::process_extend_transaction_buffer(accounts, data)?
+        }
+        MultisigInstructions::FinalizeTransactionBuffer => {
+            instructions::process_finalize_transaction_buffer(accounts, data)?
+        }
+        MultisigInstructions::CreateBatch => instructions::process_create_batch(accounts, data)?,
+        MultisigInstructions::AddBatchTransaction => {
+            instructions::process_add_batch_transaction(accounts, data)?
+        }
+        MultisigInstructions::ExecuteBatchTransaction => {
+            instructions::process_execute_batch_transaction(accounts, data)?
+        }
+        MultisigInstructions::CloseTransaction => {
+            instructions::process_close_transaction(accounts, data)?
+        }
+        MultisigInstructions::CloseTransactionBuffer => {
+            instructions::process_close_transaction_buffer(accounts, data)?
+        }
     }
 
     Ok(())
@@ -0,0 +1,122 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+use crate::error::MultisigError;
+use crate::helper::account_init::StateDefinition;
+
+/// Ordered list of transactions approved by a single proposal and executed one after the
+/// other, all signed by the same vault.
+///
+/// Batches take the next transaction index of the multisig like transactions do, so stale
+/// batches are invalidated the same way. The transactions are stored after the state, each
+/// as `len: u16` (little endian) followed by a `TransactionMessage` of `len` bytes.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct BatchState {
+    /// Multisig this batch belongs to
+    pub multisig: Pubkey,
+    /// Member creating the batch, the only one allowed to add transactions to it
+    pub creator: Pubkey,
+    /// Proposal approving this batch, set once when the proposal is created. Transactions
+    /// can't be added to the batch anymore afterwards.
+    pub proposal: Pubkey,
+    pub transaction_index: u64,
+    pub num_transactions: u8,
+    /// Transactions execute in order, so the first `num_executed` ones already executed
+    pub num_executed: u8,
+    pub bump: u8,
+    /// Vault signing the transactions, 0 for the treasury
+    pub vault_index: u8,
+    pub _padding: [u8; 4],
+}
+
+impl StateDefinition for BatchState {
+    const LEN: usize = core::mem::size_of::<BatchState>();
+    const SEED: &'static str = "batch";
+}
+
+impl BatchState {
+    /// Bound by the `u8` transaction counters
+    pub const MAX_TRANSACTIONS: usize = 255;
+
+    pub fn from_account_info_unchecked(account_info: &AccountInfo) -> &mut Self {
+        unsafe { &mut *(account_info.borrow_mut_data_unchecked().as_ptr() as *mut Self) }
+    }
+
+    pub fn from_account_info(account_info: &AccountInfo) -> Result<&mut Self, ProgramError> {
+        if account_info.data_len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(Self::from_account_info_unchecked(account_info))
+    }
+
+    pub fn validate_pda(
+        pda: &Pubkey,
+        multisig: &Pubkey,
+        transaction_index: u64,
+        bump: u8,
+    ) -> Result<(), ProgramError> {
+        let seeds = &[
+            Self::SEED.as_bytes(),
+            multisig.as_slice(),
+            &transaction_index.to_le_bytes(),
+        ];
+        let derived = pinocchio_pubkey::derive_address(seeds, Some(bump), &crate::ID);
+        if derived != *pda {
+            return Err(MultisigError::PdaMismatch.into());
+        }
+        Ok(())
+    }
+
+    /// The batch stored in `account`, `None` if `account` is not a batch PDA of `multisig`.
+    /// Lets instructions taking a transaction account accept a batch as well.
+    pub fn load<'a>(account: &'a AccountInfo, multisig: &Pubkey) -> Option<&'a mut Self> {
+        if account.owner() != &crate::ID {
+            return None;
+        }
+
+        let batch = Self::from_account_info(account).ok()?;
+        Self::validate_pda(account.key(), multisig, batch.transaction_index, batch.bump).ok()?;
+        Some(batch)
+    }
+
+    /// The stored transactions, covered by the proposal hash
+    pub fn transactions(account: &AccountInfo) -> Result<&[u8], ProgramError> {
+        unsafe { account.borrow_data_unchecked() }
+            .get(Self::LEN..)
+            .ok_or(ProgramError::InvalidAccountData)
+    }
+
+    /// Message of the transaction at `index`
+    pub fn transaction(account: &AccountInfo, index: u8) -> Result<&[u8], ProgramError> {
+        let transactions = Self::transactions(account)?;
+        let mut offset = 0;
+
+        for current in 0..=index {
+            let len = transactions
+                .get(offset..offset + 2)
+                .map(|len| u16::from_le_bytes([len[0], len[1]]) as usize)
+                .ok_or(MultisigError::InvalidPayload)?;
+            offset += 2;
+
+            if current == index {
+                return transactions
+                    .get(offset..offset + len)
+                    .ok_or(MultisigError::InvalidPayload.into());
+            }
+            offset += len;
+        }
+
+        Err(MultisigError::InvalidPayload.into())
+    }
+
+    pub fn is_executed(&self, index: u8) -> bool {
+        index < self.num_executed
+    }
+
+    /// Marks the next transaction as executed, returns true once every transaction executed
+    pub fn mark_next_executed(&mut self) -> bool {
+        self.num_executed += 1;
+        self.num_executed == self.num_transactions
+    }
+}
@@ -15,14 +15,52 @@ pub enum MemberRole {
     Member = 0,
 }
 
+/// Flags of `MemberState.permissions`
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Permission {
+    /// Create transactions and proposals
+    Initiate = 1 << 0,
+    /// Vote on proposals
+    Vote = 1 << 1,
+    /// Execute approved transactions and config changes
+    Execute = 1 << 2,
+    /// Apply approved member changes
+    ManageMembers = 1 << 3,
+}
+
 #[repr(C)]
 #[derive(Pod, Zeroable, Clone, Copy, Debug, PartialEq)]
 pub struct MemberState {
     pub pubkey: Pubkey,
+    /// Voting weight, added to the proposal yes/no weight when this member votes
+    pub weight: u16,
+    /// Bitmask of `Permission` flags
+    pub permissions: u8,
+    pub _padding: [u8; 5],
 }
 
 impl MemberState {
-    pub const LEN: usize = 32;
+    pub const LEN: usize = size_of::<MemberState>();
+
+    pub const ALL_PERMISSIONS: u8 = Permission::Initiate as u8
+        | Permission::Vote as u8
+        | Permission::Execute as u8
+        | Permission::ManageMembers as u8;
+
+    /// Permissions given when none are passed: admins get all of them, members can
+    /// vote and execute but not initiate
+    pub fn default_permissions(role: u8) -> u8 {
+        if role == MemberRole::Admin as u8 {
+            Self::ALL_PERMISSIONS
+        } else {
+            Permission::Vote as u8 | Permission::Execute as u8
+        }
+    }
+
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.permissions & permission as u8 != 0
+    }
 
     #[inline]
     pub fn from_account_info_unchecked(account_info: &AccountInfo) -> &mut Self {
@@ -39,18 +77,23 @@ impl MemberState {
     }
 
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProgramError> {
-        if bytes.len() < 32 {
+        if bytes.len() < Self::LEN {
             return Err(ProgramError::InvalidAccountData);
         }
         let pubkey_bytes = unsafe { *(bytes.as_ptr() as *const [u8; 32]) };
         Ok(MemberState {
             pubkey: Pubkey::from(pubkey_bytes),
+            weight: u16::from_le_bytes([bytes[32], bytes[33]]),
+            permissions: bytes[34],
+            _padding: [0; 5],
         })
     }
 
     pub fn to_bytes(&self) -> Result<[u8; Self::LEN], ProgramError> {
         let mut bytes = [0u8; Self::LEN];
-        bytes.copy_from_slice(&self.pubkey.as_ref());
+        bytes[..32].copy_from_slice(&self.pubkey.as_ref());
+        bytes[32..34].copy_from_slice(&self.weight.to_le_bytes());
+        bytes[34] = self.permissions;
         Ok(bytes)
     }
 }
@@ -1,9 +1,15 @@
+pub mod batch;
 pub mod member;
 pub mod multisig;
 pub mod proposal;
+pub mod spending_limit;
 pub mod transaction;
+pub mod transaction_buffer;
 
+pub use batch::*;
 pub use member::*;
 pub use multisig::*;
 pub use proposal::*;
+pub use spending_limit::*;
 pub use transaction::*;
+pub use transaction_buffer::*;
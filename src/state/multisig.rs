@@ -3,7 +3,9 @@ use core::mem::size_of;
 
 use bytemuck::{Pod, Zeroable};
 
+use crate::error::MultisigError;
 use crate::helper::account_init::StateDefinition;
+use crate::state::member::{MemberState, Permission};
 use crate::instructions::init_multisig::InitMultisigIxData;
 
 #[derive(Pod, Zeroable, Debug, Clone, Copy, PartialEq)]
@@ -14,9 +16,9 @@ pub struct MultisigState {
     pub spending_limit: u64,
     /// Maximum expiry time for proposals
     pub max_expiry: u64,
-    /// The index of the last transaction executed
+    /// The index of the last transaction created
     pub transaction_index: u64,
-    // Last stale transaction index. All transactions up until this index are stale.
+    /// Last stale transaction index. All transactions up until this index are stale.
     pub stale_transaction_index: u64,
     pub primary_seed: u16,
     /// Treasury account for the multisig, optional
@@ -29,7 +31,20 @@ pub struct MultisigState {
     pub min_threshold: u8,
     pub num_members: u8,
     pub admin_counter: u8,
-    pub _padding: [u8; 1],
+    /// Number of cancel votes that cancel an active or approved proposal
+    pub cancel_threshold: u8,
+    /// Minimum summed weight of yes votes required, on top of `min_threshold`
+    pub weight_threshold: u16,
+    /// Sum of the weights of all members
+    pub total_weight: u16,
+    /// Seconds an approved proposal has to wait before it can be executed
+    pub time_lock: u32,
+    /// Length in seconds of an admin spending limit period
+    pub spending_period: u64,
+    /// Unix timestamp at which the current spending limit period started
+    pub period_start: u64,
+    /// Lamports spent from the treasury by admins in the current period
+    pub period_spent: u64,
 }
 
 impl StateDefinition for MultisigState {
@@ -38,6 +53,9 @@ impl StateDefinition for MultisigState {
 }
 
 impl MultisigState {
+    /// Spending limit period of new multisigs, one day
+    pub const DEFAULT_SPENDING_PERIOD: u64 = 86_400;
+
     pub fn from_account_info_unchecked(account_info: &AccountInfo) -> &mut Self {
         unsafe { &mut *(account_info.borrow_mut_data_unchecked().as_ptr() as *mut Self) }
     }
@@ -69,18 +87,167 @@ impl MultisigState {
         self.num_members = 0;
         self.admin_counter = 0;
         self.primary_seed = ix_data.primary_seed;
-        self._padding = [0; 1];
+        // Cancelling takes as many votes as approving unless configured otherwise
+        self.cancel_threshold = match ix_data.cancel_threshold {
+            0 => ix_data.min_threshold,
+            cancel_threshold => cancel_threshold,
+        };
+        self.weight_threshold = ix_data.weight_threshold;
+        self.total_weight = 0;
+        self.time_lock = 0;
+        self.spending_period = Self::DEFAULT_SPENDING_PERIOD;
+        self.period_start = 0;
+        self.period_spent = 0;
+    }
+
+    /// Returns the position of `pubkey` in the member list stored after the header
+    pub fn member_index(multisig_account: &AccountInfo, pubkey: &Pubkey) -> Option<usize> {
+        let member_data = unsafe { multisig_account.borrow_data_unchecked() }.get(Self::LEN..)?;
+
+        member_data
+            .chunks_exact(MemberState::LEN)
+            .position(|member| &member[..32] == pubkey.as_ref())
+    }
+
+    /// Returns the member entry of `pubkey` if it holds `permission`
+    pub fn check_permission(
+        multisig_account: &AccountInfo,
+        pubkey: &Pubkey,
+        permission: Permission,
+    ) -> Result<MemberState, ProgramError> {
+        let index = Self::member_index(multisig_account, pubkey).ok_or(MultisigError::NotAMember)?;
+        let member_data = unsafe { multisig_account.borrow_data_unchecked() };
+        let member = MemberState::from_bytes(&member_data[Self::LEN + index * MemberState::LEN..])?;
+
+        if !member.has_permission(permission) {
+            return Err(MultisigError::MissingPermission.into());
+        }
+
+        Ok(member)
+    }
+
+    /// Number and summed weight of the members holding `Permission::Vote`, the only
+    /// members whose votes count towards the thresholds
+    pub fn voters(multisig_account: &AccountInfo) -> Result<(u8, u16), ProgramError> {
+        let member_data = unsafe { multisig_account.borrow_data_unchecked() }
+            .get(Self::LEN..)
+            .ok_or(ProgramError::InvalidAccountData)?;
+
+        let mut num_voters = 0u8;
+        let mut voter_weight = 0u16;
+        for member in member_data.chunks_exact(MemberState::LEN) {
+            let member = MemberState::from_bytes(member)?;
+            if member.has_permission(Permission::Vote) {
+                num_voters = num_voters.saturating_add(1);
+                voter_weight = voter_weight.saturating_add(member.weight);
+            }
+        }
+
+        Ok((num_voters, voter_weight))
     }
 
-    pub fn update_threshold(&mut self, threshold: u8) {
+    pub fn update_threshold(
+        &mut self,
+        multisig_account: &AccountInfo,
+        threshold: u8,
+    ) -> Result<(), ProgramError> {
         self.min_threshold = threshold;
+        self.check_threshold(multisig_account)?;
+        self.invalidate_transactions();
+        Ok(())
+    }
+
+    /// Enforces `1 <= min_threshold <= voters`, `1 <= cancel_threshold <= voters` and
+    /// `weight_threshold <= voter weight`, counting only members who can vote. Checked whenever
+    /// a threshold or the member set changes, so a multisig can never end up unable to approve
+    /// or cancel anything.
+    pub fn check_threshold(&self, multisig_account: &AccountInfo) -> Result<(), ProgramError> {
+        if self.min_threshold == 0 || self.cancel_threshold == 0 {
+            return Err(MultisigError::InvalidThreshold.into());
+        }
+
+        let (num_voters, voter_weight) = Self::voters(multisig_account)?;
+        if self.min_threshold > num_voters
+            || self.cancel_threshold > num_voters
+            || self.weight_threshold > voter_weight
+        {
+            return Err(MultisigError::ThresholdUnreachable.into());
+        }
+        Ok(())
+    }
+
+    pub fn update_cancel_threshold(
+        &mut self,
+        multisig_account: &AccountInfo,
+        cancel_threshold: u8,
+    ) -> Result<(), ProgramError> {
+        self.cancel_threshold = cancel_threshold;
+        self.check_threshold(multisig_account)?;
+        self.invalidate_transactions();
+        Ok(())
+    }
+
+    pub fn update_weight_threshold(
+        &mut self,
+        multisig_account: &AccountInfo,
+        weight_threshold: u16,
+    ) -> Result<(), ProgramError> {
+        self.weight_threshold = weight_threshold;
+        self.check_threshold(multisig_account)?;
+        self.invalidate_transactions();
+        Ok(())
+    }
+
+    pub fn update_time_lock(&mut self, time_lock: u32) {
+        self.time_lock = time_lock;
     }
 
     pub fn update_spending_limit(&mut self, spending_limit: u64) {
         self.spending_limit = spending_limit;
     }
 
-    pub fn update_stale_transaction_index(&mut self, stale_transaction_index: u64) {
+    pub fn update_spending_period(&mut self, spending_period: u64) -> Result<(), ProgramError> {
+        if spending_period == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        self.spending_period = spending_period;
+        Ok(())
+    }
+
+    /// Counts `amount` against the admin spending limit. A new period starts once
+    /// `spending_period` seconds passed since the current one started.
+    pub fn spend(&mut self, amount: u64, current_time: u64) -> Result<(), ProgramError> {
+        if current_time >= self.period_start.saturating_add(self.spending_period) {
+            self.period_start = current_time;
+            self.period_spent = 0;
+        }
+
+        self.period_spent = self
+            .period_spent
+            .checked_add(amount)
+            .filter(|spent| *spent <= self.spending_limit)
+            .ok_or(MultisigError::SpendingLimitExceeded)?;
+
+        Ok(())
+    }
+
+    pub fn update_stale_transaction_index(
+        &mut self,
+        stale_transaction_index: u64,
+    ) -> Result<(), ProgramError> {
+        // Stale transactions can never become valid again
+        if stale_transaction_index < self.stale_transaction_index
+            || stale_transaction_index > self.transaction_index
+        {
+            return Err(MultisigError::InvalidStaleTransactionIndex.into());
+        }
         self.stale_transaction_index = stale_transaction_index;
+        Ok(())
+    }
+
+    /// Marks every transaction created so far as stale. Called whenever the member set
+    /// or the threshold changes, so votes cast under the old config can't be executed.
+    pub fn invalidate_transactions(&mut self) {
+        self.stale_transaction_index = self.transaction_index;
     }
 }
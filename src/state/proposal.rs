@@ -1,17 +1,33 @@
-use crate::helper::account_init::StateDefinition;
-use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+use crate::error::MultisigError;
+use crate::helper::{account_init::StateDefinition, utils::hash};
+use crate::state::{batch::BatchState, multisig::MultisigState, transaction::TransactionState};
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{clock::Clock, Sysvar},
+};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[repr(C)]
 pub struct ProposalState {
     pub proposal_id: u16, // Unique identifier for the proposal
-    pub expiry: u64,      // Adjust size as needed is it needed here?
+    pub expiry: u64,      // Unix timestamp after which the proposal can no longer pass
     pub created_time: u64,
     pub status: ProposalStatus,
-    pub bump: u8,          // Bump seed for PDA
-    pub yes_votes: u8,     // Number of yes votes
-    pub no_votes: u8,      // Number of no votes
-    pub _padding: [u8; 4], // padding to reach multiple of 8
+    pub bump: u8,            // Bump seed for PDA
+    pub yes_votes: u8,       // Number of yes votes
+    pub no_votes: u8,        // Number of no votes
+    pub creator: Pubkey,     // Receives the proposal rent back on close
+    pub transaction: Pubkey, // Transaction PDA approved by this proposal
+    pub tx_hash: [u8; 32],   // Hash of the transaction payload when the proposal was created
+    pub transaction_index: u64, // Index of the approved transaction, checked against the stale index
+    pub yes_weight: u16,     // Summed weight of the yes voters
+    pub no_weight: u16,      // Summed weight of the no voters
+    pub cancel_votes: u8,    // Number of cancel votes, stored after the yes, no and abstain votes
+    pub abstain_votes: u8,   // Number of abstain votes
+    pub abstain_weight: u16, // Summed weight of the abstaining voters
+    pub approved_at: u64,    // Unix timestamp at which the proposal became `Succeeded`
 }
 
 impl StateDefinition for ProposalState {
@@ -46,11 +62,173 @@ impl ProposalState {
         ];
         let derived = pinocchio_pubkey::derive_address(seeds, Some(proposal_bump), &crate::ID);
         if derived != *pda {
-            return Err(ProgramError::InvalidAccountOwner);
+            return Err(MultisigError::PdaMismatch.into());
         }
         Ok(())
     }
 
+    /// Loads a proposal of `multisig_account` that reached its vote and weight thresholds and
+    /// was not executed, failed or cancelled yet. With a `time_lock` set on the multisig, the
+    /// proposal must have been `Succeeded` for at least that long. The proposal and `transaction_account`
+    /// must point at each other and the payload must not have changed since creation.
+    /// Proposals for transactions at or below the stale transaction index are rejected.
+    /// Handlers applying a change themselves also check `is_program_instruction` on the
+    /// transaction, so the proposal only authorizes the exact instruction it holds.
+    pub fn load_approved(
+        proposal_account: &AccountInfo,
+        multisig_account: &AccountInfo,
+        transaction_account: &AccountInfo,
+        multisig: &MultisigState,
+    ) -> Result<Self, ProgramError> {
+        let proposal = Self::load_executable(proposal_account, multisig_account, multisig)?;
+
+        if transaction_account.owner() != &crate::ID {
+            return Err(ProgramError::IllegalOwner);
+        }
+
+        let transaction = TransactionState::from_account_info(transaction_account)?;
+
+        if proposal.transaction.ne(transaction_account.key())
+            || transaction.proposal.ne(proposal_account.key())
+            || hash(TransactionState::load_payload(transaction_account)?) != proposal.tx_hash
+        {
+            return Err(MultisigError::TransactionMismatch.into());
+        }
+
+        Ok(proposal)
+    }
+
+    /// Same as `load_approved` for a proposal approving the batch stored in `batch_account`
+    pub fn load_approved_batch(
+        proposal_account: &AccountInfo,
+        multisig_account: &AccountInfo,
+        batch_account: &AccountInfo,
+        multisig: &MultisigState,
+    ) -> Result<Self, ProgramError> {
+        let proposal = Self::load_executable(proposal_account, multisig_account, multisig)?;
+
+        let batch = BatchState::load(batch_account, multisig_account.key())
+            .ok_or(MultisigError::TransactionMismatch)?;
+
+        if proposal.transaction.ne(batch_account.key())
+            || batch.proposal.ne(proposal_account.key())
+            || hash(BatchState::transactions(batch_account)?) != proposal.tx_hash
+        {
+            return Err(MultisigError::TransactionMismatch.into());
+        }
+
+        Ok(proposal)
+    }
+
+    /// Proposal checks shared by `load_approved` and `load_approved_batch`
+    fn load_executable(
+        proposal_account: &AccountInfo,
+        multisig_account: &AccountInfo,
+        multisig: &MultisigState,
+    ) -> Result<Self, ProgramError> {
+        if proposal_account.owner() != &crate::ID {
+            return Err(ProgramError::IllegalOwner);
+        }
+
+        let proposal = Self::from_bytes(unsafe { proposal_account.borrow_data_unchecked() })?;
+
+        Self::validate_pda(
+            proposal_account.key(),
+            multisig_account.key(),
+            proposal.bump,
+            proposal.proposal_id,
+        )?;
+
+        match proposal.status {
+            ProposalStatus::Executed | ProposalStatus::Failed | ProposalStatus::Cancelled => {
+                return Err(MultisigError::ProposalFinalized.into());
+            }
+            _ => {}
+        }
+
+        if !proposal.is_approved(multisig) {
+            return Err(MultisigError::ProposalNotApproved.into());
+        }
+
+        if proposal.is_stale(multisig) {
+            return Err(MultisigError::ProposalStale.into());
+        }
+
+        let current_time = Clock::get()?.unix_timestamp as u64;
+
+        // Proposals finalized as `Succeeded` before expiry stay executable
+        if proposal.status != ProposalStatus::Succeeded && proposal.is_expired(current_time) {
+            return Err(MultisigError::ProposalExpired.into());
+        }
+
+        if proposal.is_time_locked(multisig, current_time) {
+            return Err(MultisigError::TimeLockActive.into());
+        }
+
+        Ok(proposal)
+    }
+
+    /// Both the member count and the summed weight of the yes votes reached their threshold
+    pub fn is_approved(&self, multisig: &MultisigState) -> bool {
+        self.yes_votes >= multisig.min_threshold && self.yes_weight >= multisig.weight_threshold
+    }
+
+    /// Even if every member who can vote and didn't vote no or abstain voted yes, a threshold
+    /// can't be met
+    pub fn is_unreachable(
+        &self,
+        multisig: &MultisigState,
+        multisig_account: &AccountInfo,
+    ) -> Result<bool, ProgramError> {
+        let (num_voters, voter_weight) = MultisigState::voters(multisig_account)?;
+        let withheld_votes = self.no_votes.saturating_add(self.abstain_votes);
+        let withheld_weight = self.no_weight.saturating_add(self.abstain_weight);
+
+        Ok(num_voters.saturating_sub(withheld_votes) < multisig.min_threshold
+            || voter_weight.saturating_sub(withheld_weight) < multisig.weight_threshold)
+    }
+
+    /// Number of yes, no and abstain votes, which are stored in that order before the cancel votes
+    pub fn cast_votes(&self) -> usize {
+        self.yes_votes as usize + self.no_votes as usize + self.abstain_votes as usize
+    }
+
+    /// Marks the proposal `Succeeded`, which starts the multisig time lock
+    pub fn approve(&mut self, current_time: u64) {
+        self.status = ProposalStatus::Succeeded;
+        self.approved_at = current_time;
+    }
+
+    /// The multisig time lock hasn't elapsed since the proposal was approved. Without a
+    /// time lock, proposals that reached the thresholds but weren't finalized are executable too.
+    pub fn is_time_locked(&self, multisig: &MultisigState, current_time: u64) -> bool {
+        if multisig.time_lock == 0 {
+            return false;
+        }
+
+        self.status != ProposalStatus::Succeeded
+            || current_time < self.approved_at.saturating_add(multisig.time_lock as u64)
+    }
+
+    /// Votes cast before the last config change can't approve anything anymore
+    pub fn is_stale(&self, multisig: &MultisigState) -> bool {
+        self.transaction_index <= multisig.stale_transaction_index
+    }
+
+    pub fn is_expired(&self, current_time: u64) -> bool {
+        current_time >= self.expiry
+    }
+
+    /// Consumes an approved proposal so it can never be used again. Called before any CPI,
+    /// so a reentrant call can't replay it.
+    pub fn mark_executed(&mut self, proposal_account: &AccountInfo) {
+        self.status = ProposalStatus::Executed;
+        unsafe {
+            proposal_account.borrow_mut_data_unchecked()[..Self::LEN]
+                .copy_from_slice(self.to_bytes().as_ref());
+        }
+    }
+
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProgramError> {
         if bytes.len() < Self::LEN {
             return Err(ProgramError::InvalidAccountData);
@@ -68,7 +246,22 @@ impl ProposalState {
             bump: bytes[19],
             yes_votes: bytes[20],
             no_votes: bytes[21],
-            _padding: [0; 4],
+            creator: Pubkey::from(*unsafe { &*(bytes[22..54].as_ptr() as *const [u8; 32]) }),
+            transaction: Pubkey::from(*unsafe { &*(bytes[54..86].as_ptr() as *const [u8; 32]) }),
+            tx_hash: *unsafe { &*(bytes[86..118].as_ptr() as *const [u8; 32]) },
+            transaction_index: u64::from_le_bytes([
+                bytes[118], bytes[119], bytes[120], bytes[121], bytes[122], bytes[123], bytes[124],
+                bytes[125],
+            ]),
+            yes_weight: u16::from_le_bytes([bytes[126], bytes[127]]),
+            no_weight: u16::from_le_bytes([bytes[128], bytes[129]]),
+            cancel_votes: bytes[130],
+            abstain_votes: bytes[131],
+            abstain_weight: u16::from_le_bytes([bytes[132], bytes[133]]),
+            approved_at: u64::from_le_bytes([
+                bytes[134], bytes[135], bytes[136], bytes[137], bytes[138], bytes[139], bytes[140],
+                bytes[141],
+            ]),
         })
     }
 
@@ -81,7 +274,16 @@ impl ProposalState {
         bytes[19] = self.bump;
         bytes[20] = self.yes_votes;
         bytes[21] = self.no_votes;
-        bytes[22..26].copy_from_slice(&self._padding);
+        bytes[22..54].copy_from_slice(&self.creator);
+        bytes[54..86].copy_from_slice(&self.transaction);
+        bytes[86..118].copy_from_slice(&self.tx_hash);
+        bytes[118..126].copy_from_slice(&self.transaction_index.to_le_bytes());
+        bytes[126..128].copy_from_slice(&self.yes_weight.to_le_bytes());
+        bytes[128..130].copy_from_slice(&self.no_weight.to_le_bytes());
+        bytes[130] = self.cancel_votes;
+        bytes[131] = self.abstain_votes;
+        bytes[132..134].copy_from_slice(&self.abstain_weight.to_le_bytes());
+        bytes[134..142].copy_from_slice(&self.approved_at.to_le_bytes());
         bytes
     }
 }
@@ -94,6 +296,7 @@ pub enum ProposalStatus {
     Failed = 2,
     Succeeded = 3,
     Cancelled = 4,
+    Executed = 5,
 }
 
 impl TryFrom<&u8> for ProposalStatus {
@@ -106,6 +309,7 @@ impl TryFrom<&u8> for ProposalStatus {
             2 => Ok(ProposalStatus::Failed),
             3 => Ok(ProposalStatus::Succeeded),
             4 => Ok(ProposalStatus::Cancelled),
+            5 => Ok(ProposalStatus::Executed),
             _ => Err(ProgramError::InvalidInstructionData),
         }
     }
@@ -119,11 +323,25 @@ impl ProposalState {
         status: ProposalStatus,
         bump: u8,
         created_time: u64,
+        creator: &Pubkey,
+        transaction: &Pubkey,
+        tx_hash: [u8; 32],
+        transaction_index: u64,
     ) {
         self.proposal_id = proposal_id;
         self.expiry = expiry;
         self.created_time = created_time;
         self.status = status;
         self.bump = bump;
+        self.creator = *creator;
+        self.transaction = *transaction;
+        self.tx_hash = tx_hash;
+        self.transaction_index = transaction_index;
+        self.yes_weight = 0;
+        self.no_weight = 0;
+        self.cancel_votes = 0;
+        self.abstain_votes = 0;
+        self.abstain_weight = 0;
+        self.approved_at = 0;
     }
 }
@@ -0,0 +1,164 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+use crate::error::MultisigError;
+use crate::helper::account_init::StateDefinition;
+
+/// Amount that allowed members can spend from one vault without a vote, per period.
+///
+/// The pubkeys of the allowed members (`num_members`) and of the allowed destinations
+/// (`num_destinations`, none for any destination) are stored after the state.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct SpendingLimitState {
+    /// Multisig this spending limit belongs to
+    pub multisig: Pubkey,
+    /// Token mint, all zeroes for native SOL
+    pub mint: Pubkey,
+    /// Amount that can be spent per period
+    pub amount: u64,
+    /// Amount left in the current period
+    pub remaining_amount: u64,
+    /// Unix timestamp at which the current period started
+    pub last_reset: u64,
+    /// Seed of the spending limit PDA
+    pub seed: u16,
+    /// `SpendingLimitPeriod`
+    pub period: u8,
+    pub bump: u8,
+    pub num_members: u8,
+    pub num_destinations: u8,
+    /// Vault the amount is spent from, 0 for the treasury
+    pub vault_index: u8,
+    pub _padding: [u8; 1],
+}
+
+impl StateDefinition for SpendingLimitState {
+    const LEN: usize = core::mem::size_of::<SpendingLimitState>();
+    const SEED: &'static str = "spending_limit";
+}
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SpendingLimitPeriod {
+    /// The amount is never replenished
+    OneTime = 0,
+    Daily = 1,
+    Weekly = 2,
+    /// 30 days
+    Monthly = 3,
+}
+
+impl SpendingLimitPeriod {
+    /// Length of the period in seconds, `None` for one-time limits
+    pub fn seconds(self) -> Option<u64> {
+        match self {
+            SpendingLimitPeriod::OneTime => None,
+            SpendingLimitPeriod::Daily => Some(86_400),
+            SpendingLimitPeriod::Weekly => Some(7 * 86_400),
+            SpendingLimitPeriod::Monthly => Some(30 * 86_400),
+        }
+    }
+}
+
+impl TryFrom<u8> for SpendingLimitPeriod {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(SpendingLimitPeriod::OneTime),
+            1 => Ok(SpendingLimitPeriod::Daily),
+            2 => Ok(SpendingLimitPeriod::Weekly),
+            3 => Ok(SpendingLimitPeriod::Monthly),
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
+
+impl SpendingLimitState {
+    pub fn from_account_info_unchecked(account_info: &AccountInfo) -> &mut Self {
+        unsafe { &mut *(account_info.borrow_mut_data_unchecked().as_ptr() as *mut Self) }
+    }
+
+    pub fn from_account_info(account_info: &AccountInfo) -> Result<&mut Self, ProgramError> {
+        if account_info.data_len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(Self::from_account_info_unchecked(account_info))
+    }
+
+    pub fn validate_pda(
+        pda: &Pubkey,
+        multisig: &Pubkey,
+        seed: u16,
+        bump: u8,
+    ) -> Result<(), ProgramError> {
+        let seeds = &[Self::SEED.as_bytes(), multisig.as_slice(), &seed.to_le_bytes()];
+        let derived = pinocchio_pubkey::derive_address(seeds, Some(bump), &crate::ID);
+        if derived != *pda {
+            return Err(MultisigError::PdaMismatch.into());
+        }
+        Ok(())
+    }
+
+    /// Account size for a spending limit with `num_members` members and `num_destinations`
+    /// destinations
+    pub fn size(num_members: usize, num_destinations: usize) -> usize {
+        Self::LEN + (num_members + num_destinations) * 32
+    }
+
+    /// Checks `member` is allowed to use the spending limit stored in `account`
+    pub fn check_member(&self, account: &AccountInfo, member: &Pubkey) -> Result<(), ProgramError> {
+        let data = unsafe { account.borrow_data_unchecked() };
+        let members = data
+            .get(Self::LEN..Self::LEN + self.num_members as usize * 32)
+            .ok_or(ProgramError::InvalidAccountData)?;
+
+        if !members.chunks_exact(32).any(|allowed| allowed == member.as_ref()) {
+            return Err(MultisigError::MemberNotAllowed.into());
+        }
+        Ok(())
+    }
+
+    /// Checks `destination` is allowed by the spending limit stored in `account`
+    pub fn check_destination(
+        &self,
+        account: &AccountInfo,
+        destination: &Pubkey,
+    ) -> Result<(), ProgramError> {
+        if self.num_destinations == 0 {
+            return Ok(());
+        }
+
+        let start = Self::LEN + self.num_members as usize * 32;
+        let data = unsafe { account.borrow_data_unchecked() };
+        let destinations = data
+            .get(start..start + self.num_destinations as usize * 32)
+            .ok_or(ProgramError::InvalidAccountData)?;
+
+        if !destinations.chunks_exact(32).any(|allowed| allowed == destination.as_ref()) {
+            return Err(MultisigError::DestinationNotAllowed.into());
+        }
+        Ok(())
+    }
+
+    /// Counts `amount` against the spending limit. The remaining amount is replenished for
+    /// every full period that passed since `last_reset`.
+    pub fn spend(&mut self, amount: u64, current_time: u64) -> Result<(), ProgramError> {
+        if let Some(period) = SpendingLimitPeriod::try_from(self.period)?.seconds() {
+            let elapsed_periods = current_time.saturating_sub(self.last_reset) / period;
+
+            if elapsed_periods > 0 {
+                self.last_reset = self.last_reset.saturating_add(elapsed_periods * period);
+                self.remaining_amount = self.amount;
+            }
+        }
+
+        self.remaining_amount = self
+            .remaining_amount
+            .checked_sub(amount)
+            .ok_or(MultisigError::SpendingLimitExceeded)?;
+
+        Ok(())
+    }
+}
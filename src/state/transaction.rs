@@ -1,20 +1,31 @@
 use pinocchio::{
     account_info::AccountInfo,
     program_error::ProgramError,
+    pubkey::Pubkey,
     ProgramResult,
 };
 use bytemuck::{Pod, Zeroable};
-use crate::instructions::create_transaction::CreateTransactionIxData;
+use crate::error::MultisigError;
 use crate::helper::account_init::StateDefinition;
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq, shank::ShankAccount, Pod, Zeroable)]
 pub struct TransactionState {
     pub transaction_index: u64,
+    /// Multisig this transaction belongs to
+    pub multisig: Pubkey,
+    /// Proposal approving this transaction, set once when the proposal is created
+    pub proposal: Pubkey,
     pub buffer_size: u16,
+    /// Payloads up to 512 bytes. Larger payloads, uploaded through a `TransactionBuffer`,
+    /// are stored after the state instead.
     pub tx_buffer: [u8; 512],
     pub bump: u8,
-    pub _padding: [u8; 5],
+    /// Vault signing the stored instructions, 0 for the treasury
+    pub vault_index: u8,
+    /// Paid the rent of the transaction, gets it back when the transaction is closed
+    pub rent_payer: Pubkey,
+    pub _padding: [u8; 4],
 }
 
 impl StateDefinition for TransactionState {
@@ -34,18 +45,386 @@ impl TransactionState {
         Ok(Self::from_account_info_unchecked(account_info))
     }
 
+    pub fn validate_pda(
+        pda: &Pubkey,
+        multisig: &Pubkey,
+        transaction_index: u64,
+        bump: u8,
+    ) -> Result<(), ProgramError> {
+        let seeds = &[
+            Self::SEED.as_bytes(),
+            multisig.as_slice(),
+            &transaction_index.to_le_bytes(),
+        ];
+        let derived = pinocchio_pubkey::derive_address(seeds, Some(bump), &crate::ID);
+        if derived != *pda {
+            return Err(MultisigError::PdaMismatch.into());
+        }
+        Ok(())
+    }
+
+    /// The serialized instructions, `tx_buffer[..buffer_size]`. Fails for payloads stored
+    /// after the state, use `load_payload` to read those too.
+    pub fn payload(&self) -> Result<&[u8], ProgramError> {
+        self.tx_buffer
+            .get(..self.buffer_size as usize)
+            .ok_or(ProgramError::InvalidAccountData)
+    }
+
+    /// Account size of a transaction carrying a payload of `payload_len` bytes
+    pub fn size(payload_len: usize) -> usize {
+        if payload_len <= 512 {
+            Self::LEN
+        } else {
+            Self::LEN + payload_len
+        }
+    }
+
+    /// The serialized instructions of the transaction stored in `transaction_acc`, wherever
+    /// they are stored
+    pub fn load_payload(transaction_acc: &AccountInfo) -> Result<&[u8], ProgramError> {
+        let transaction = Self::from_account_info(transaction_acc)?;
+
+        if transaction.buffer_size as usize <= transaction.tx_buffer.len() {
+            return transaction.payload();
+        }
+
+        unsafe { transaction_acc.borrow_data_unchecked() }
+            .get(Self::LEN..Self::LEN + transaction.buffer_size as usize)
+            .ok_or(ProgramError::InvalidAccountData)
+    }
+
+    /// Returns true if the payload is a single instruction to this program carrying
+    /// `discriminator` followed by `data`. Config changes are approved this way.
+    pub fn is_program_instruction(&self, discriminator: u8, data: &[u8]) -> bool {
+        let Ok(message) = self.payload().and_then(TransactionMessage::parse) else {
+            return false;
+        };
+
+        let Some(instruction) = message.instructions().next() else {
+            return false;
+        };
+
+        message.num_instructions == 1
+            && message.account_key(instruction.program_id_index) == Some(&crate::ID)
+            && instruction.data.split_first() == Some((&discriminator, data))
+    }
+
+    /// Writes the state of a transaction created with `size(payload.len())` bytes
     pub fn initialize(
         transaction_acc: &AccountInfo,
-        ix_data: &CreateTransactionIxData,
+        multisig: &Pubkey,
+        transaction_index: u64,
+        payload: &[u8],
+        vault_index: u8,
         bump: u8,
+        rent_payer: &Pubkey,
     ) -> ProgramResult {
         let transaction_state = TransactionState::from_account_info(&transaction_acc)?;
 
-        transaction_state.transaction_index = ix_data.transaction_index;
-        transaction_state.tx_buffer = ix_data.tx_buffer;
-        transaction_state.buffer_size = ix_data.buffer_size;
+        transaction_state.transaction_index = transaction_index;
+        transaction_state.multisig = *multisig;
+        transaction_state.buffer_size =
+            u16::try_from(payload.len()).map_err(|_| ProgramError::InvalidInstructionData)?;
         transaction_state.bump = bump;
+        transaction_state.vault_index = vault_index;
+        transaction_state.rent_payer = *rent_payer;
+
+        if payload.len() <= transaction_state.tx_buffer.len() {
+            transaction_state.tx_buffer[..payload.len()].copy_from_slice(payload);
+        } else {
+            unsafe { transaction_acc.borrow_mut_data_unchecked() }
+                .get_mut(Self::LEN..Self::LEN + payload.len())
+                .ok_or(ProgramError::AccountDataTooSmall)?
+                .copy_from_slice(payload);
+        }
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+/// Zero-copy view of a transaction payload.
+///
+/// Message layout, version 0:
+/// - `version: u8`, `TransactionMessage::VERSION`
+/// - `num_signers: u8`, the first `num_signers` account keys sign
+/// - `num_writable_signers: u8`, the first `num_writable_signers` signers are writable
+/// - `num_writable_non_signers: u8`, the first `num_writable_non_signers` keys after the
+///   signers are writable
+/// - `num_account_keys: u8`
+/// - `account_keys: [[u8; 32]; num_account_keys]`, unique
+/// - `num_instructions: u8`, at least one
+/// - for each instruction:
+///   - `program_id_index: u8`
+///   - `num_accounts: u8`
+///   - `account_indexes: [u8; num_accounts]`
+///   - `data_len: u16` (little endian)
+///   - `data: [u8; data_len]`
+/// - `num_address_table_lookups: u8`
+/// - for each address lookup table:
+///   - `account_key: [u8; 32]`
+///   - `num_writable_indexes: u8`
+///   - `writable_indexes: [u8; num_writable_indexes]`
+///   - `num_readonly_indexes: u8`
+///   - `readonly_indexes: [u8; num_readonly_indexes]`
+///
+/// Indexes refer to `account_keys`, followed by the writable addresses loaded from every
+/// lookup table and then the read-only ones, like v0 transactions. Loaded addresses never
+/// sign and programs can't be loaded. `parse` checks every bound, so a parsed message can
+/// be walked without further checks.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TransactionMessage<'a> {
+    pub num_signers: u8,
+    pub num_writable_signers: u8,
+    pub num_writable_non_signers: u8,
+    pub num_instructions: u8,
+    pub num_address_table_lookups: u8,
+    /// Addresses loaded from the lookup tables, writable ones first
+    pub num_loaded_writable: u8,
+    pub num_loaded_readonly: u8,
+    account_keys: &'a [u8],
+    instructions: &'a [u8],
+    address_table_lookups: &'a [u8],
+}
+
+/// Instruction of a `TransactionMessage`, referencing its accounts by index
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CompiledInstruction<'a> {
+    pub program_id_index: u8,
+    pub account_indexes: &'a [u8],
+    pub data: &'a [u8],
+}
+
+/// Address lookup table referenced by a `TransactionMessage`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MessageAddressTableLookup<'a> {
+    pub account_key: &'a Pubkey,
+    pub writable_indexes: &'a [u8],
+    pub readonly_indexes: &'a [u8],
+}
+
+impl<'a> TransactionMessage<'a> {
+    pub const VERSION: u8 = 0;
+
+    /// Parses and validates `payload`, malformed payloads fail with `InvalidPayload`
+    pub fn parse(payload: &'a [u8]) -> Result<Self, ProgramError> {
+        let invalid = || ProgramError::from(MultisigError::InvalidPayload);
+
+        let [version, num_signers, num_writable_signers, num_writable_non_signers, num_account_keys, rest @ ..] =
+            payload
+        else {
+            return Err(invalid());
+        };
+
+        if *version != Self::VERSION
+            || num_signers > num_account_keys
+            || num_writable_signers > num_signers
+            || *num_writable_non_signers > num_account_keys - num_signers
+        {
+            return Err(invalid());
+        }
+
+        let keys_len = *num_account_keys as usize * 32;
+        let account_keys = rest.get(..keys_len).ok_or_else(invalid)?;
+
+        for (index, key) in account_keys.chunks_exact(32).enumerate() {
+            if account_keys[..index * 32].chunks_exact(32).any(|other| other == key) {
+                return Err(invalid());
+            }
+        }
+
+        let (num_instructions, instructions) =
+            rest[keys_len..].split_first().ok_or_else(invalid)?;
+
+        if *num_instructions == 0 {
+            return Err(invalid());
+        }
+
+        let mut offset = 0;
+        for _ in 0..*num_instructions {
+            let (_, len) = read_instruction(instructions, offset).ok_or_else(invalid)?;
+            offset += len;
+        }
+
+        let (instructions, lookups) = instructions.split_at(offset);
+        let (num_address_table_lookups, address_table_lookups) =
+            lookups.split_first().ok_or_else(invalid)?;
+
+        let (mut num_loaded_writable, mut num_loaded_readonly) = (0usize, 0usize);
+        let mut offset = 0;
+        for _ in 0..*num_address_table_lookups {
+            let (lookup, len) =
+                read_address_table_lookup(address_table_lookups, offset).ok_or_else(invalid)?;
+            num_loaded_writable += lookup.writable_indexes.len();
+            num_loaded_readonly += lookup.readonly_indexes.len();
+            offset += len;
+        }
+
+        // No trailing bytes, the payload hash covers exactly the parsed message
+        if offset != address_table_lookups.len() {
+            return Err(invalid());
+        }
+
+        let num_accounts = *num_account_keys as usize + num_loaded_writable + num_loaded_readonly;
+        if num_accounts > u8::MAX as usize + 1 {
+            return Err(invalid());
+        }
+
+        let mut offset = 0;
+        for _ in 0..*num_instructions {
+            let (instruction, len) = read_instruction(instructions, offset).ok_or_else(invalid)?;
+
+            if instruction.program_id_index >= *num_account_keys
+                || instruction
+                    .account_indexes
+                    .iter()
+                    .any(|index| *index as usize >= num_accounts)
+            {
+                return Err(invalid());
+            }
+
+            offset += len;
+        }
+
+        Ok(Self {
+            num_signers: *num_signers,
+            num_writable_signers: *num_writable_signers,
+            num_writable_non_signers: *num_writable_non_signers,
+            num_instructions: *num_instructions,
+            num_address_table_lookups: *num_address_table_lookups,
+            num_loaded_writable: num_loaded_writable as u8,
+            num_loaded_readonly: num_loaded_readonly as u8,
+            account_keys,
+            instructions,
+            address_table_lookups,
+        })
+    }
+
+    pub fn num_account_keys(&self) -> usize {
+        self.account_keys.len() / 32
+    }
+
+    /// Accounts indexes can refer to, including the loaded addresses
+    pub fn num_accounts(&self) -> usize {
+        self.num_account_keys() + self.num_loaded_writable as usize + self.num_loaded_readonly as usize
+    }
+
+    /// Key at `index` of `account_keys`, `None` for loaded addresses
+    pub fn account_key(&self, index: u8) -> Option<&'a Pubkey> {
+        let start = index as usize * 32;
+        self.account_keys
+            .get(start..start + 32)
+            .and_then(|key| key.try_into().ok())
+    }
+
+    pub fn is_signer(&self, index: u8) -> bool {
+        index < self.num_signers
+    }
+
+    pub fn is_writable(&self, index: u8) -> bool {
+        let num_account_keys = self.num_account_keys();
+
+        if self.is_signer(index) {
+            index < self.num_writable_signers
+        } else if (index as usize) < num_account_keys {
+            index - self.num_signers < self.num_writable_non_signers
+        } else {
+            (index as usize - num_account_keys) < self.num_loaded_writable as usize
+        }
+    }
+
+    pub fn instructions(&self) -> CompiledInstructions<'a> {
+        CompiledInstructions {
+            instructions: self.instructions,
+            offset: 0,
+        }
+    }
+
+    pub fn address_table_lookups(&self) -> AddressTableLookups<'a> {
+        AddressTableLookups {
+            lookups: self.address_table_lookups,
+            offset: 0,
+        }
+    }
+}
+
+/// Iterator over the instructions of a parsed `TransactionMessage`
+pub struct CompiledInstructions<'a> {
+    instructions: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Iterator for CompiledInstructions<'a> {
+    type Item = CompiledInstruction<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (instruction, len) = read_instruction(self.instructions, self.offset)?;
+        self.offset += len;
+        Some(instruction)
+    }
+}
+
+/// Iterator over the address lookup tables of a parsed `TransactionMessage`
+pub struct AddressTableLookups<'a> {
+    lookups: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Iterator for AddressTableLookups<'a> {
+    type Item = MessageAddressTableLookup<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (lookup, len) = read_address_table_lookup(self.lookups, self.offset)?;
+        self.offset += len;
+        Some(lookup)
+    }
+}
+
+/// Reads the instruction at `offset`, returns it with its length in bytes
+fn read_instruction(bytes: &[u8], offset: usize) -> Option<(CompiledInstruction<'_>, usize)> {
+    let program_id_index = *bytes.get(offset)?;
+    let num_accounts = *bytes.get(offset + 1)? as usize;
+
+    let accounts_start = offset + 2;
+    let account_indexes = bytes.get(accounts_start..accounts_start + num_accounts)?;
+
+    let data_len_start = accounts_start + num_accounts;
+    let data_len = bytes.get(data_len_start..data_len_start + 2)?;
+    let data_len = u16::from_le_bytes([data_len[0], data_len[1]]) as usize;
+
+    let data_start = data_len_start + 2;
+    let data = bytes.get(data_start..data_start + data_len)?;
+
+    Some((
+        CompiledInstruction {
+            program_id_index,
+            account_indexes,
+            data,
+        },
+        data_start + data_len - offset,
+    ))
+}
+
+/// Reads the address lookup table at `offset`, returns it with its length in bytes
+fn read_address_table_lookup(
+    bytes: &[u8],
+    offset: usize,
+) -> Option<(MessageAddressTableLookup<'_>, usize)> {
+    let account_key = bytes.get(offset..offset + 32)?.try_into().ok()?;
+
+    let num_writable = *bytes.get(offset + 32)? as usize;
+    let writable_start = offset + 33;
+    let writable_indexes = bytes.get(writable_start..writable_start + num_writable)?;
+
+    let num_readonly = *bytes.get(writable_start + num_writable)? as usize;
+    let readonly_start = writable_start + num_writable + 1;
+    let readonly_indexes = bytes.get(readonly_start..readonly_start + num_readonly)?;
+
+    Some((
+        MessageAddressTableLookup {
+            account_key,
+            writable_indexes,
+            readonly_indexes,
+        },
+        readonly_start + num_readonly - offset,
+    ))
+}
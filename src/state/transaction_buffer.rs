@@ -0,0 +1,76 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+use crate::error::MultisigError;
+use crate::helper::account_init::StateDefinition;
+
+/// Transaction payload uploaded in chunks, for payloads larger than the 512 bytes a
+/// `CreateTransaction` instruction can carry.
+///
+/// The bytes uploaded so far are stored after the state, the account grows with every chunk.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct TransactionBufferState {
+    /// Multisig the transaction will be created in
+    pub multisig: Pubkey,
+    /// Member uploading the payload, the only one allowed to extend the buffer
+    pub creator: Pubkey,
+    /// SHA-256 of the complete payload
+    pub final_hash: [u8; 32],
+    /// Size of the complete payload
+    pub final_size: u16,
+    /// Seed of the buffer PDA
+    pub seed: u16,
+    pub bump: u8,
+    /// Vault signing the instructions, 0 for the treasury
+    pub vault_index: u8,
+}
+
+impl StateDefinition for TransactionBufferState {
+    const LEN: usize = core::mem::size_of::<TransactionBufferState>();
+    const SEED: &'static str = "transaction_buffer";
+}
+
+impl TransactionBufferState {
+    /// Largest payload a buffer can hold. The transaction created from it has to stay under
+    /// the 10 KiB an account can be created with from a program.
+    pub const MAX_SIZE: usize = 8_192;
+
+    pub fn from_account_info_unchecked(account_info: &AccountInfo) -> &mut Self {
+        unsafe { &mut *(account_info.borrow_mut_data_unchecked().as_ptr() as *mut Self) }
+    }
+
+    pub fn from_account_info(account_info: &AccountInfo) -> Result<&mut Self, ProgramError> {
+        if account_info.data_len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(Self::from_account_info_unchecked(account_info))
+    }
+
+    pub fn validate_pda(
+        pda: &Pubkey,
+        multisig: &Pubkey,
+        creator: &Pubkey,
+        seed: u16,
+        bump: u8,
+    ) -> Result<(), ProgramError> {
+        let seeds = &[
+            Self::SEED.as_bytes(),
+            multisig.as_slice(),
+            creator.as_slice(),
+            &seed.to_le_bytes(),
+        ];
+        let derived = pinocchio_pubkey::derive_address(seeds, Some(bump), &crate::ID);
+        if derived != *pda {
+            return Err(MultisigError::PdaMismatch.into());
+        }
+        Ok(())
+    }
+
+    /// The bytes uploaded so far to the buffer stored in `account`
+    pub fn buffer(account: &AccountInfo) -> Result<&[u8], ProgramError> {
+        unsafe { account.borrow_data_unchecked() }
+            .get(Self::LEN..)
+            .ok_or(ProgramError::InvalidAccountData)
+    }
+}
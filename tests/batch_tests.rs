@@ -0,0 +1,286 @@
+use pinocchio_multisig::{
+    error::MultisigError,
+    helper::StateDefinition,
+    state::{BatchState, ProposalState, ProposalStatus},
+};
+use solana_sdk::{
+    instruction::{AccountMeta, InstructionError},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    system_instruction, system_program,
+    transaction::TransactionError,
+};
+
+mod common;
+
+fn batch_state(svm: &litesvm::LiteSVM, pda_batch: &Pubkey) -> BatchState {
+    let batch_account = svm.get_account(pda_batch).unwrap();
+    *bytemuck::from_bytes(&batch_account.data[..BatchState::LEN])
+}
+
+#[test]
+pub fn test_execute_batch() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    let (pda_multisig, multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let (pda_treasury, _) =
+        Pubkey::find_program_address(&[b"treasury", pda_multisig.as_ref()], &program_id);
+    svm.airdrop(&pda_treasury, 10_000_000).unwrap();
+
+    // One transfer per transaction, all approved by a single vote
+    let recipients: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+    let pda_batch = common::create_batch(&mut svm, &second_admin, program_id, pda_multisig, 0);
+
+    for recipient in &recipients {
+        let transfer_ix = system_instruction::transfer(&pda_treasury, recipient, 1_000_000);
+        let result = common::add_batch_transaction(
+            &mut svm,
+            &second_admin,
+            program_id,
+            pda_multisig,
+            pda_batch,
+            &common::encode_instructions(&[transfer_ix]),
+        );
+        println!("add batch transaction result: {:?}", result);
+        assert!(result.is_ok());
+    }
+    assert_eq!(batch_state(&svm, &pda_batch).num_transactions, 3);
+
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_batch,
+    );
+    common::activate_proposal(&mut svm, &second_admin, program_id, pda_multisig, pda_proposal);
+
+    for voter in [&second_admin, &third_admin] {
+        common::vote(
+            &mut svm,
+            voter,
+            program_id,
+            pda_multisig,
+            multisig_bump,
+            pda_proposal,
+            proposal_bump,
+            1,
+        );
+    }
+
+    let remaining = |recipient: &Pubkey| {
+        vec![
+            AccountMeta::new(*recipient, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ]
+    };
+
+    // Batch transactions can't be executed as a single transaction
+    let result = common::execute_transaction(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_batch,
+        remaining(&recipients[0]),
+    );
+    assert!(result.is_err());
+
+    let result = common::execute_batch_transaction(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_batch,
+        remaining(&recipients[0]),
+    );
+    println!("execute batch transaction result: {:?}", result);
+    assert!(result.is_ok());
+
+    // The second transaction fails without its recipient, the batch stays where it was
+    svm.expire_blockhash();
+    let result = common::execute_batch_transaction(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_batch,
+        remaining(&recipients[0]),
+    );
+    assert!(result.is_err());
+
+    let batch = batch_state(&svm, &pda_batch);
+    assert_eq!(batch.num_executed, 1);
+    assert!(batch.is_executed(0));
+    assert!(!batch.is_executed(1));
+
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let proposal_state = ProposalState::from_bytes(&proposal_account.data).unwrap();
+    assert_eq!(proposal_state.status, ProposalStatus::Succeeded);
+
+    // The partially executed batch resumes with the second transaction
+    for recipient in &recipients[1..] {
+        let result = common::execute_batch_transaction(
+            &mut svm,
+            &second_admin,
+            program_id,
+            pda_multisig,
+            pda_proposal,
+            pda_batch,
+            remaining(recipient),
+        );
+        assert!(result.is_ok());
+    }
+
+    for recipient in &recipients {
+        assert_eq!(svm.get_account(recipient).unwrap().lamports, 1_000_000);
+    }
+    // The treasury was created with its rent-exempt minimum of 890_880 lamports
+    assert_eq!(svm.get_account(&pda_treasury).unwrap().lamports, 7_890_880);
+
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let proposal_state = ProposalState::from_bytes(&proposal_account.data).unwrap();
+    assert_eq!(proposal_state.status, ProposalStatus::Executed);
+
+    svm.expire_blockhash();
+    let result = common::execute_batch_transaction(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_batch,
+        remaining(&recipients[2]),
+    );
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::ProposalFinalized as u32)
+        )
+    );
+
+    // The executed batch is closed like a transaction, its rent goes back to the creator
+    let result = common::close_transaction(
+        &mut svm,
+        &third_admin,
+        program_id,
+        pda_multisig,
+        pda_batch,
+        second_admin.pubkey(),
+        vec![AccountMeta::new_readonly(pda_proposal, false)],
+    );
+    println!("close batch result: {:?}", result);
+    assert!(result.is_ok());
+    assert!(svm.get_account(&pda_batch).map_or(true, |account| account.lamports == 0));
+}
+
+#[test]
+pub fn test_add_batch_transaction_checks() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    let (pda_multisig, _) = common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let (pda_treasury, _) =
+        Pubkey::find_program_address(&[b"treasury", pda_multisig.as_ref()], &program_id);
+    let transfer_ix = system_instruction::transfer(&pda_treasury, &Pubkey::new_unique(), 1_000_000);
+    let payload = common::encode_instructions(&[transfer_ix]);
+
+    let pda_batch = common::create_batch(&mut svm, &second_admin, program_id, pda_multisig, 0);
+
+    // Empty batches can't be proposed
+    let result = common::try_create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_batch,
+        0,
+    );
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::InvalidPayload as u32)
+        )
+    );
+
+    // Only the creator adds transactions
+    let result = common::add_batch_transaction(
+        &mut svm,
+        &third_admin,
+        program_id,
+        pda_multisig,
+        pda_batch,
+        &payload,
+    );
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::CreatorMismatch as u32)
+        )
+    );
+
+    // Malformed messages are rejected when added
+    let result = common::add_batch_transaction(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_batch,
+        &payload[..payload.len() - 1],
+    );
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::InvalidPayload as u32)
+        )
+    );
+
+    let result = common::add_batch_transaction(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_batch,
+        &payload,
+    );
+    assert!(result.is_ok());
+
+    svm.expire_blockhash();
+    common::create_proposal(&mut svm, &second_admin, program_id, pda_multisig, pda_batch);
+
+    // The proposal froze the batch
+    svm.expire_blockhash();
+    let result = common::add_batch_transaction(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_batch,
+        &payload,
+    );
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::TransactionAlreadyClaimed as u32)
+        )
+    );
+}
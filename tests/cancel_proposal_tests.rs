@@ -0,0 +1,120 @@
+use pinocchio_multisig::{
+    error::MultisigError,
+    helper::StateDefinition,
+    state::{ProposalState, ProposalStatus},
+};
+use solana_sdk::{
+    instruction::{AccountMeta, InstructionError},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    system_instruction, system_program,
+    transaction::TransactionError,
+};
+
+mod common;
+
+#[test]
+pub fn test_cancel_approved_proposal() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    let (pda_multisig, multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let (pda_treasury, _) =
+        Pubkey::find_program_address(&[b"treasury", pda_multisig.as_ref()], &program_id);
+    svm.airdrop(&pda_treasury, 10_000_000).unwrap();
+
+    let recipient = Pubkey::new_unique();
+    let transfer_ix = system_instruction::transfer(&pda_treasury, &recipient, 1_000_000);
+    let pda_transaction = common::create_transaction(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        &[transfer_ix],
+    );
+
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+    );
+    common::activate_proposal(&mut svm, &second_admin, program_id, pda_multisig, pda_proposal);
+
+    for voter in [&second_admin, &third_admin] {
+        common::vote(
+            &mut svm,
+            voter,
+            program_id,
+            pda_multisig,
+            multisig_bump,
+            pda_proposal,
+            proposal_bump,
+            1,
+        );
+    }
+
+    // The cancel threshold defaults to min_threshold (2)
+    let result =
+        common::cancel_proposal(&mut svm, &second_admin, program_id, pda_multisig, pda_proposal);
+    assert!(result.is_ok());
+
+    // A second cancel vote from the same member doesn't count
+    svm.expire_blockhash();
+    let result =
+        common::cancel_proposal(&mut svm, &second_admin, program_id, pda_multisig, pda_proposal);
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::AlreadyVoted as u32)
+        )
+    );
+
+    let result =
+        common::cancel_proposal(&mut svm, &third_admin, program_id, pda_multisig, pda_proposal);
+    assert!(result.is_ok());
+
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let proposal_state = ProposalState::from_bytes(&proposal_account.data).unwrap();
+    assert_eq!(proposal_state.status, ProposalStatus::Cancelled);
+    assert_eq!(proposal_state.cancel_votes, 2);
+    assert_eq!(proposal_account.data.len(), ProposalState::LEN + 4 * 32);
+
+    // The approved transaction can't be executed anymore
+    let remaining = vec![
+        AccountMeta::new(recipient, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+    let result = common::execute_transaction(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_transaction,
+        remaining,
+    );
+    assert!(result.is_err());
+    assert!(svm.get_account(&recipient).is_none());
+
+    // Closing refunds the yes and the cancel votes
+    let result = common::close_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        second_admin.pubkey(),
+        vec![third_admin.pubkey()],
+    );
+    assert!(result.is_ok());
+    assert!(svm.get_account(&pda_proposal).map_or(true, |account| account.lamports == 0));
+}
@@ -0,0 +1,269 @@
+use pinocchio_multisig::state::{ProposalState, ProposalStatus};
+use solana_sdk::{
+    instruction::AccountMeta, pubkey::Pubkey, signature::Keypair, signer::Signer,
+    system_instruction, system_program,
+};
+
+mod common;
+
+#[test]
+pub fn test_creator_cancels_proposal_without_votes() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    let (pda_multisig, _multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let pda_transaction =
+        common::create_placeholder_transaction(&mut svm, &second_admin, program_id, pda_multisig);
+    let (pda_proposal, _proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+    );
+
+    let proposal_lamports = svm.get_account(&pda_proposal).unwrap().lamports;
+    let creator_lamports = svm.get_account(&second_admin.pubkey()).unwrap().lamports;
+
+    let result = common::close_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        second_admin.pubkey(),
+        vec![],
+    );
+    println!("close proposal result: {:?}", result);
+    assert!(result.is_ok());
+
+    let closed = svm.get_account(&pda_proposal);
+    assert!(closed.is_none() || closed.unwrap().lamports == 0);
+
+    // Creator paid the transaction fee but got the proposal rent back
+    let creator_after = svm.get_account(&second_admin.pubkey()).unwrap().lamports;
+    assert!(creator_after > creator_lamports + proposal_lamports - 10_000);
+}
+
+#[test]
+pub fn test_close_open_proposal_by_other_member_fails() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    let (pda_multisig, _multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let pda_transaction =
+        common::create_placeholder_transaction(&mut svm, &second_admin, program_id, pda_multisig);
+    let (pda_proposal, _proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+    );
+
+    let result = common::close_proposal(
+        &mut svm,
+        &third_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        second_admin.pubkey(),
+        vec![],
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+pub fn test_close_executed_proposal_refunds_voters() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    let (pda_multisig, multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let (pda_treasury, _) =
+        Pubkey::find_program_address(&[b"treasury", pda_multisig.as_ref()], &program_id);
+    svm.airdrop(&pda_treasury, 10_000_000).unwrap();
+
+    let recipient = Pubkey::new_unique();
+    let transfer_ix = system_instruction::transfer(&pda_treasury, &recipient, 1_000_000);
+    let pda_transaction = common::create_transaction(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        &[transfer_ix],
+    );
+
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+    );
+    common::activate_proposal(&mut svm, &second_admin, program_id, pda_multisig, pda_proposal);
+
+    for voter in [&second_admin, &third_admin] {
+        common::vote(
+            &mut svm,
+            voter,
+            program_id,
+            pda_multisig,
+            multisig_bump,
+            pda_proposal,
+            proposal_bump,
+            1,
+        );
+    }
+
+    // Approved but not executed yet: the proposal is finalized and kept open
+    let result = common::close_proposal(
+        &mut svm,
+        &fee_payer,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        second_admin.pubkey(),
+        vec![second_admin.pubkey(), third_admin.pubkey()],
+    );
+    assert!(result.is_ok());
+
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let proposal_state = ProposalState::from_bytes(&proposal_account.data).unwrap();
+    assert_eq!(proposal_state.status, ProposalStatus::Succeeded);
+
+    let result = common::execute_transaction(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_transaction,
+        vec![
+            AccountMeta::new(recipient, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+    );
+    assert!(result.is_ok());
+
+    let voter_lamports = svm.get_account(&third_admin.pubkey()).unwrap().lamports;
+
+    svm.expire_blockhash();
+    let result = common::close_proposal(
+        &mut svm,
+        &fee_payer,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        second_admin.pubkey(),
+        vec![second_admin.pubkey(), third_admin.pubkey()],
+    );
+    println!("close executed proposal result: {:?}", result);
+    assert!(result.is_ok());
+
+    let closed = svm.get_account(&pda_proposal);
+    assert!(closed.is_none() || closed.unwrap().lamports == 0);
+    assert!(svm.get_account(&third_admin.pubkey()).unwrap().lamports > voter_lamports);
+}
+
+#[test]
+pub fn test_close_stale_succeeded_proposal() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    let (pda_multisig, multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let (pda_treasury, _) =
+        Pubkey::find_program_address(&[b"treasury", pda_multisig.as_ref()], &program_id);
+    let transfer_ix = system_instruction::transfer(&pda_treasury, &Pubkey::new_unique(), 1_000_000);
+    let pda_transaction = common::create_transaction(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        &[transfer_ix],
+    );
+
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+    );
+    common::activate_proposal(&mut svm, &second_admin, program_id, pda_multisig, pda_proposal);
+
+    for voter in [&second_admin, &third_admin] {
+        common::vote(
+            &mut svm,
+            voter,
+            program_id,
+            pda_multisig,
+            multisig_bump,
+            pda_proposal,
+            proposal_bump,
+            1,
+        );
+    }
+
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let proposal_state = ProposalState::from_bytes(&proposal_account.data).unwrap();
+    assert_eq!(proposal_state.status, ProposalStatus::Succeeded);
+
+    // Lowering the threshold leaves the approved proposal unexecutable
+    let data = common::update_multisig_data(1, 0, 1);
+    let (pda_config_proposal, pda_config_transaction) = common::approve_config_change(
+        &mut svm,
+        &[&second_admin, &third_admin],
+        program_id,
+        pda_multisig,
+        data.clone(),
+        1,
+    );
+    let result = common::update_multisig(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_config_proposal,
+        pda_config_transaction,
+        data,
+    );
+    assert!(result.is_ok());
+
+    let voter_lamports = svm.get_account(&third_admin.pubkey()).unwrap().lamports;
+
+    let result = common::close_proposal(
+        &mut svm,
+        &fee_payer,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        second_admin.pubkey(),
+        vec![second_admin.pubkey(), third_admin.pubkey()],
+    );
+    println!("close stale proposal result: {:?}", result);
+    assert!(result.is_ok());
+
+    let closed = svm.get_account(&pda_proposal);
+    assert!(closed.is_none() || closed.unwrap().lamports == 0);
+    assert!(svm.get_account(&third_admin.pubkey()).unwrap().lamports > voter_lamports);
+}
@@ -0,0 +1,191 @@
+use pinocchio_multisig::error::MultisigError;
+use solana_sdk::{
+    instruction::{AccountMeta, InstructionError},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    system_instruction, system_program,
+    transaction::TransactionError,
+};
+
+mod common;
+
+fn not_closable() -> TransactionError {
+    TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(MultisigError::TransactionNotClosable as u32),
+    )
+}
+
+#[test]
+pub fn test_close_executed_transaction() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    let (pda_multisig, multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let (pda_treasury, _) =
+        Pubkey::find_program_address(&[b"treasury", pda_multisig.as_ref()], &program_id);
+    svm.airdrop(&pda_treasury, 10_000_000).unwrap();
+
+    let recipient = Pubkey::new_unique();
+    let transfer_ix = system_instruction::transfer(&pda_treasury, &recipient, 1_000_000);
+    let pda_transaction =
+        common::create_transaction(&mut svm, &second_admin, program_id, pda_multisig, &[transfer_ix]);
+
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+    );
+    common::activate_proposal(&mut svm, &second_admin, program_id, pda_multisig, pda_proposal);
+
+    for voter in [&second_admin, &third_admin] {
+        common::vote(
+            &mut svm,
+            voter,
+            program_id,
+            pda_multisig,
+            multisig_bump,
+            pda_proposal,
+            proposal_bump,
+            1,
+        );
+    }
+
+    // Approved but not executed yet
+    let result = common::close_transaction(
+        &mut svm,
+        &third_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+        second_admin.pubkey(),
+        vec![AccountMeta::new_readonly(pda_proposal, false)],
+    );
+    assert_eq!(result.unwrap_err().err, not_closable());
+
+    let result = common::execute_transaction(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_transaction,
+        vec![
+            AccountMeta::new(recipient, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+    );
+    assert!(result.is_ok());
+
+    // The rent goes back to the member who created the transaction
+    let result = common::close_transaction(
+        &mut svm,
+        &third_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+        third_admin.pubkey(),
+        vec![AccountMeta::new_readonly(pda_proposal, false)],
+    );
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::RentPayerMismatch as u32)
+        )
+    );
+
+    let rent = svm.get_account(&pda_transaction).unwrap().lamports;
+    let payer_lamports = svm.get_account(&second_admin.pubkey()).unwrap().lamports;
+
+    svm.expire_blockhash();
+    let result = common::close_transaction(
+        &mut svm,
+        &third_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+        second_admin.pubkey(),
+        vec![AccountMeta::new_readonly(pda_proposal, false)],
+    );
+    println!("close transaction result: {:?}", result);
+    assert!(result.is_ok());
+
+    assert!(svm.get_account(&pda_transaction).map_or(true, |account| account.lamports == 0));
+    assert_eq!(
+        svm.get_account(&second_admin.pubkey()).unwrap().lamports,
+        payer_lamports + rent
+    );
+}
+
+#[test]
+pub fn test_close_stale_transaction() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    let (pda_multisig, _) = common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let pda_treasury =
+        Pubkey::find_program_address(&[b"treasury", pda_multisig.as_ref()], &program_id).0;
+    let transfer_ix = system_instruction::transfer(&pda_treasury, &Pubkey::new_unique(), 1_000_000);
+    let pda_transaction =
+        common::create_transaction(&mut svm, &second_admin, program_id, pda_multisig, &[transfer_ix]);
+
+    // Nobody proposed it yet, it can still be
+    let result = common::close_transaction(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+        second_admin.pubkey(),
+        vec![],
+    );
+    assert_eq!(result.unwrap_err().err, not_closable());
+
+    // Changing the threshold invalidates every transaction created before
+    let data = common::update_multisig_data(1, 0, 1);
+    let (pda_proposal, pda_config_transaction) = common::approve_config_change(
+        &mut svm,
+        &[&second_admin, &third_admin],
+        program_id,
+        pda_multisig,
+        data.clone(),
+        0,
+    );
+    let result = common::update_multisig(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_config_transaction,
+        data,
+    );
+    assert!(result.is_ok());
+
+    svm.expire_blockhash();
+    let result = common::close_transaction(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+        second_admin.pubkey(),
+        vec![],
+    );
+    println!("close transaction result: {:?}", result);
+    assert!(result.is_ok());
+    assert!(svm.get_account(&pda_transaction).map_or(true, |account| account.lamports == 0));
+}
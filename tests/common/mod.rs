@@ -5,7 +5,7 @@ use litesvm::{
 use pinocchio_multisig::{
     helper::StateDefinition,
     instructions::{CreateProposalIxData, VoteIxData},
-    state::ProposalState,
+    state::{MultisigState, ProposalState},
     ID,
 };
 use solana_sdk::{
@@ -62,22 +62,70 @@ pub fn create_multisig(
     program_id: Pubkey,
     admins: Vec<Pubkey>,
 ) -> (Pubkey, u8) {
+    create_weighted_multisig(svm, fee_payer, program_id, admins, &[], &[], 0)
+}
+
+/// Same as `create_multisig`, with one weight and one permission bitmask per admin
+/// (empty for a weight of 1 and all permissions each)
+pub fn create_weighted_multisig(
+    svm: &mut LiteSVM,
+    fee_payer: &Keypair,
+    program_id: Pubkey,
+    admins: Vec<Pubkey>,
+    weights: &[u16],
+    permissions: &[u8],
+    weight_threshold: u16,
+) -> (Pubkey, u8) {
+    let result = try_create_weighted_multisig(
+        svm,
+        fee_payer,
+        program_id,
+        admins,
+        weights,
+        permissions,
+        weight_threshold,
+    );
+    assert!(result.is_ok());
+
     let multisig_seed = [(b"multisig"), &0u16.to_le_bytes() as &[u8]];
-    let (pda_multisig, multisig_bump) = Pubkey::find_program_address(&multisig_seed, &program_id);
+    Pubkey::find_program_address(&multisig_seed, &program_id)
+}
+
+/// Same as `create_weighted_multisig`, returning the result instead of asserting it
+pub fn try_create_weighted_multisig(
+    svm: &mut LiteSVM,
+    fee_payer: &Keypair,
+    program_id: Pubkey,
+    admins: Vec<Pubkey>,
+    weights: &[u16],
+    permissions: &[u8],
+    weight_threshold: u16,
+) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+    let multisig_seed = [(b"multisig"), &0u16.to_le_bytes() as &[u8]];
+    let (pda_multisig, _multisig_bump) = Pubkey::find_program_address(&multisig_seed, &program_id);
     let treasury_seed = [(b"treasury"), pda_multisig.as_ref()];
     let treasury_seeds = &treasury_seed[..];
     let (pda_treasury, _treasury_bump) = Pubkey::find_program_address(treasury_seeds, &program_id);
     let init_multisig = InitMultisigIxData {
         max_expiry: 1_000_000,
         primary_seed: 0,
-        min_threshold: 2,
+        min_threshold: admins.len().min(2) as u8,
         num_members: admins.len() as u8,
         num_admins: admins.len() as u8,
+        cancel_threshold: 0,
+        weight_threshold,
     };
 
     let mut ix_data = vec![0u8];
 
     ix_data.extend_from_slice(unsafe { to_bytes(&init_multisig) });
+    if !weights.is_empty() || !permissions.is_empty() {
+        for i in 0..admins.len() {
+            let weight = weights.get(i).copied().unwrap_or(1);
+            ix_data.extend_from_slice(&weight.to_le_bytes());
+        }
+    }
+    ix_data.extend_from_slice(permissions);
 
     let mut accounts = vec![
         AccountMeta::new(fee_payer.pubkey(), true),
@@ -98,10 +146,7 @@ pub fn create_multisig(
         data: ix_data,
     };
 
-    let result = build_and_send_transaction(svm, fee_payer, vec![init_ix]);
-    assert!(result.is_ok());
-
-    (pda_multisig, multisig_bump)
+    build_and_send_transaction(svm, fee_payer, vec![init_ix])
 }
 
 pub fn create_proposal(
@@ -109,17 +154,52 @@ pub fn create_proposal(
     fee_payer: &Keypair,
     program_id: Pubkey,
     multisig_pda: Pubkey,
+    transaction_pda: Pubkey,
+) -> (Pubkey, u8) {
+    create_proposal_with_seed(svm, fee_payer, program_id, multisig_pda, transaction_pda, 0)
+}
+
+pub fn create_proposal_with_seed(
+    svm: &mut LiteSVM,
+    fee_payer: &Keypair,
+    program_id: Pubkey,
+    multisig_pda: Pubkey,
+    transaction_pda: Pubkey,
+    primary_seed: u16,
 ) -> (Pubkey, u8) {
     let proposal_seed = &[
         ProposalState::SEED.as_bytes(),
         multisig_pda.as_ref(),
-        &0u16.to_le_bytes(),
+        &primary_seed.to_le_bytes(),
     ];
     let (pda_proposal, proposal_bump) = Pubkey::find_program_address(proposal_seed, &program_id);
 
+    let result = try_create_proposal(svm, fee_payer, program_id, multisig_pda, transaction_pda, primary_seed);
+    assert!(result.is_ok());
+    // println!("Created Proposal PDA: {:?}", pda_proposal);
+
+    (pda_proposal, proposal_bump)
+}
+
+/// Same as `create_proposal_with_seed`, returning the result instead of asserting it
+pub fn try_create_proposal(
+    svm: &mut LiteSVM,
+    fee_payer: &Keypair,
+    program_id: Pubkey,
+    multisig_pda: Pubkey,
+    transaction_pda: Pubkey,
+    primary_seed: u16,
+) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+    let proposal_seed = &[
+        ProposalState::SEED.as_bytes(),
+        multisig_pda.as_ref(),
+        &primary_seed.to_le_bytes(),
+    ];
+    let (pda_proposal, _) = Pubkey::find_program_address(proposal_seed, &program_id);
+
     let create_proposal_data = CreateProposalIxData {
         expiry: 1_000_000,
-        primary_seed: 0,
+        primary_seed,
     };
 
     let mut ix_data = vec![2u8];
@@ -131,6 +211,7 @@ pub fn create_proposal(
             AccountMeta::new(fee_payer.pubkey(), true), // creator (signer)
             AccountMeta::new(pda_proposal, false),      // proposal_account (will be created)
             AccountMeta::new_readonly(multisig_pda, false), // multisig_account (readonly)
+            AccountMeta::new(transaction_pda, false),   // transaction approved by the proposal
             AccountMeta::new_readonly(rent::ID, false), // rent sysvar
             AccountMeta::new_readonly(solana_sdk::sysvar::clock::ID, false), // clock sysvar
             AccountMeta::new_readonly(system_program::ID, false), // system program
@@ -138,11 +219,29 @@ pub fn create_proposal(
         data: ix_data,
     };
 
-    let result = build_and_send_transaction(svm, fee_payer, vec![create_proposal_ix]);
-    assert!(result.is_ok());
-    // println!("Created Proposal PDA: {:?}", pda_proposal);
+    build_and_send_transaction(svm, fee_payer, vec![create_proposal_ix])
+}
 
-    (pda_proposal, proposal_bump)
+pub fn activate_proposal(
+    svm: &mut LiteSVM,
+    fee_payer: &Keypair,
+    program_id: Pubkey,
+    multisig_pda: Pubkey,
+    proposal_pda: Pubkey,
+) {
+    let activate_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(fee_payer.pubkey(), true),
+            AccountMeta::new_readonly(multisig_pda, false),
+            AccountMeta::new(proposal_pda, false),
+        ],
+        data: vec![8u8],
+    };
+
+    let result = build_and_send_transaction(svm, fee_payer, vec![activate_ix]);
+    println!("Activate result: {:?}", result);
+    assert!(result.is_ok());
 }
 
 pub fn vote(
@@ -183,3 +282,797 @@ pub fn vote(
 
     (proposal_pda, proposal_bump)
 }
+
+pub fn serialize_instructions(instructions: &[Instruction]) -> ([u8; 512], u16) {
+    let buffer = encode_instructions(instructions);
+
+    let mut tx_buffer = [0u8; 512];
+    tx_buffer[..buffer.len()].copy_from_slice(&buffer);
+
+    (tx_buffer, buffer.len() as u16)
+}
+
+/// The `TransactionMessage` stored by a transaction executing `instructions`, of any size
+pub fn encode_instructions(instructions: &[Instruction]) -> Vec<u8> {
+    encode_message(instructions, &[])
+}
+
+/// Same as `encode_instructions`, loading the non-signer accounts found in
+/// `lookup_tables` (table key, table addresses) from those tables
+pub fn encode_message(instructions: &[Instruction], lookup_tables: &[(Pubkey, &[Pubkey])]) -> Vec<u8> {
+    // (pubkey, is_signer, is_writable), merged across instructions
+    let mut keys: Vec<(Pubkey, bool, bool)> = Vec::new();
+    let mut program_ids: Vec<Pubkey> = Vec::new();
+    let mut add_key = |pubkey: Pubkey, is_signer: bool, is_writable: bool| {
+        match keys.iter_mut().find(|(key, _, _)| *key == pubkey) {
+            Some(key) => {
+                key.1 |= is_signer;
+                key.2 |= is_writable;
+            }
+            None => keys.push((pubkey, is_signer, is_writable)),
+        }
+    };
+
+    for instruction in instructions {
+        for meta in &instruction.accounts {
+            add_key(meta.pubkey, meta.is_signer, meta.is_writable);
+        }
+        add_key(instruction.program_id, false, false);
+        program_ids.push(instruction.program_id);
+    }
+
+    let table_position = |pubkey: &Pubkey| {
+        lookup_tables.iter().enumerate().find_map(|(table, (_, addresses))| {
+            addresses
+                .iter()
+                .position(|address| address == pubkey)
+                .map(|index| (table, index as u8))
+        })
+    };
+
+    // Signers and programs can't be loaded from lookup tables
+    let (mut keys, loaded): (Vec<_>, Vec<_>) = keys.into_iter().partition(|(key, is_signer, _)| {
+        *is_signer || program_ids.contains(key) || table_position(key).is_none()
+    });
+
+    // Writable signers, read-only signers, writable non-signers, read-only non-signers
+    keys.sort_by_key(|(_, is_signer, is_writable)| (!is_signer, !is_writable));
+
+    let count = |signer: bool, writable: bool| {
+        keys.iter()
+            .filter(|(_, is_signer, is_writable)| *is_signer == signer && *is_writable == writable)
+            .count() as u8
+    };
+
+    // Loaded addresses are indexed after the keys, writable ones of every table first
+    let mut lookups: Vec<(Pubkey, Vec<u8>, Vec<u8>)> = lookup_tables
+        .iter()
+        .map(|(table, _)| (*table, Vec::new(), Vec::new()))
+        .collect();
+    let mut loaded_writable = Vec::new();
+    let mut loaded_readonly = Vec::new();
+    for (table, _) in lookup_tables.iter().enumerate() {
+        for (pubkey, _, is_writable) in &loaded {
+            let (key_table, index) = table_position(pubkey).unwrap();
+            if key_table != table {
+                continue;
+            }
+            if *is_writable {
+                lookups[table].1.push(index);
+                loaded_writable.push(*pubkey);
+            } else {
+                lookups[table].2.push(index);
+                loaded_readonly.push(*pubkey);
+            }
+        }
+    }
+
+    let index_of = |pubkey: &Pubkey| {
+        keys.iter()
+            .map(|(key, _, _)| key)
+            .chain(loaded_writable.iter())
+            .chain(loaded_readonly.iter())
+            .position(|key| key == pubkey)
+            .unwrap() as u8
+    };
+
+    let mut buffer = vec![
+        0, // version
+        count(true, true) + count(true, false),
+        count(true, true),
+        count(false, true),
+        keys.len() as u8,
+    ];
+    for (key, _, _) in &keys {
+        buffer.extend_from_slice(key.as_ref());
+    }
+
+    buffer.push(instructions.len() as u8);
+    for instruction in instructions {
+        buffer.push(index_of(&instruction.program_id));
+        buffer.push(instruction.accounts.len() as u8);
+        for meta in &instruction.accounts {
+            buffer.push(index_of(&meta.pubkey));
+        }
+        buffer.extend_from_slice(&(instruction.data.len() as u16).to_le_bytes());
+        buffer.extend_from_slice(&instruction.data);
+    }
+
+    buffer.push(lookups.len() as u8);
+    for (table, writable_indexes, readonly_indexes) in &lookups {
+        buffer.extend_from_slice(table.as_ref());
+        buffer.push(writable_indexes.len() as u8);
+        buffer.extend_from_slice(writable_indexes);
+        buffer.push(readonly_indexes.len() as u8);
+        buffer.extend_from_slice(readonly_indexes);
+    }
+
+    buffer
+}
+
+pub fn transaction_pda(svm: &LiteSVM, program_id: Pubkey, multisig_pda: Pubkey) -> Pubkey {
+    let multisig_account = svm.get_account(&multisig_pda).unwrap();
+    let multisig_state: &MultisigState =
+        bytemuck::from_bytes(&multisig_account.data[..MultisigState::LEN]);
+    let transaction_index = multisig_state.transaction_index + 1;
+
+    let (pda_transaction, _) = Pubkey::find_program_address(
+        &[
+            b"transaction",
+            multisig_pda.as_ref(),
+            &transaction_index.to_le_bytes(),
+        ],
+        &program_id,
+    );
+
+    pda_transaction
+}
+
+pub fn create_transaction(
+    svm: &mut LiteSVM,
+    fee_payer: &Keypair,
+    program_id: Pubkey,
+    multisig_pda: Pubkey,
+    instructions: &[Instruction],
+) -> Pubkey {
+    create_vault_transaction(svm, fee_payer, program_id, multisig_pda, instructions, 0)
+}
+
+/// Transaction that is only proposed and voted on, never executed. Messages need at least
+/// one instruction, so it holds an empty system program instruction.
+pub fn create_placeholder_transaction(
+    svm: &mut LiteSVM,
+    fee_payer: &Keypair,
+    program_id: Pubkey,
+    multisig_pda: Pubkey,
+) -> Pubkey {
+    let placeholder = Instruction {
+        program_id: system_program::ID,
+        accounts: vec![],
+        data: vec![],
+    };
+    create_transaction(svm, fee_payer, program_id, multisig_pda, &[placeholder])
+}
+
+/// Same as `create_transaction`, with the instructions signed by vault `vault_index`
+pub fn create_vault_transaction(
+    svm: &mut LiteSVM,
+    fee_payer: &Keypair,
+    program_id: Pubkey,
+    multisig_pda: Pubkey,
+    instructions: &[Instruction],
+    vault_index: u8,
+) -> Pubkey {
+    create_transaction_from_payload(
+        svm,
+        fee_payer,
+        program_id,
+        multisig_pda,
+        &encode_instructions(instructions),
+        vault_index,
+    )
+}
+
+/// Creates a transaction storing `payload`, an encoded `TransactionMessage` of up to 512 bytes
+pub fn create_transaction_from_payload(
+    svm: &mut LiteSVM,
+    fee_payer: &Keypair,
+    program_id: Pubkey,
+    multisig_pda: Pubkey,
+    payload: &[u8],
+    vault_index: u8,
+) -> Pubkey {
+    let pda_transaction = transaction_pda(svm, program_id, multisig_pda);
+
+    let mut tx_buffer = [0u8; 512];
+    tx_buffer[..payload.len()].copy_from_slice(payload);
+    let buffer_size = payload.len() as u16;
+
+    let data = [
+        vec![5], // discriminator for CreateTransaction instruction
+        tx_buffer.to_vec(),
+        buffer_size.to_le_bytes().to_vec(),
+        vec![vault_index, 0],
+    ]
+    .concat();
+
+    let create_transaction_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(fee_payer.pubkey(), true),
+            AccountMeta::new(multisig_pda, false),
+            AccountMeta::new(pda_transaction, false),
+            AccountMeta::new_readonly(rent::ID, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data,
+    };
+
+    let result = build_and_send_transaction(svm, fee_payer, vec![create_transaction_ix]);
+    assert!(result.is_ok());
+
+    pda_transaction
+}
+
+pub fn execute_transaction(
+    svm: &mut LiteSVM,
+    fee_payer: &Keypair,
+    program_id: Pubkey,
+    multisig_pda: Pubkey,
+    proposal_pda: Pubkey,
+    transaction_pda: Pubkey,
+    remaining: Vec<AccountMeta>,
+) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+    let (pda_treasury, _) =
+        Pubkey::find_program_address(&[b"treasury", multisig_pda.as_ref()], &program_id);
+
+    execute_vault_transaction(
+        svm,
+        fee_payer,
+        program_id,
+        multisig_pda,
+        proposal_pda,
+        transaction_pda,
+        pda_treasury,
+        remaining,
+    )
+}
+
+/// Same as `execute_transaction` for a transaction signed by `vault`
+pub fn execute_vault_transaction(
+    svm: &mut LiteSVM,
+    fee_payer: &Keypair,
+    program_id: Pubkey,
+    multisig_pda: Pubkey,
+    proposal_pda: Pubkey,
+    transaction_pda: Pubkey,
+    vault: Pubkey,
+    remaining: Vec<AccountMeta>,
+) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+    let mut accounts = vec![
+        AccountMeta::new(fee_payer.pubkey(), true),
+        AccountMeta::new_readonly(multisig_pda, false),
+        AccountMeta::new(proposal_pda, false),
+        AccountMeta::new_readonly(transaction_pda, false),
+        AccountMeta::new(vault, false),
+    ];
+    accounts.extend(remaining);
+
+    let execute_ix = Instruction {
+        program_id,
+        accounts,
+        data: vec![6u8], // discriminator for ExecuteTransaction instruction
+    };
+
+    build_and_send_transaction(svm, fee_payer, vec![execute_ix])
+}
+
+pub fn cancel_proposal(
+    svm: &mut LiteSVM,
+    fee_payer: &Keypair,
+    program_id: Pubkey,
+    multisig_pda: Pubkey,
+    proposal_pda: Pubkey,
+) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+    let cancel_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(fee_payer.pubkey(), true),
+            AccountMeta::new_readonly(multisig_pda, false),
+            AccountMeta::new(proposal_pda, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: vec![9u8], // discriminator for CancelProposal instruction
+    };
+
+    build_and_send_transaction(svm, fee_payer, vec![cancel_ix])
+}
+
+pub fn close_proposal(
+    svm: &mut LiteSVM,
+    fee_payer: &Keypair,
+    program_id: Pubkey,
+    multisig_pda: Pubkey,
+    proposal_pda: Pubkey,
+    creator: Pubkey,
+    voters: Vec<Pubkey>,
+) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+    let mut accounts = vec![
+        AccountMeta::new(fee_payer.pubkey(), true),
+        AccountMeta::new_readonly(multisig_pda, false),
+        AccountMeta::new(proposal_pda, false),
+        AccountMeta::new(creator, false),
+    ];
+    accounts.extend(voters.into_iter().map(|voter| AccountMeta::new(voter, false)));
+
+    let close_ix = Instruction {
+        program_id,
+        accounts,
+        data: vec![4u8], // discriminator for CloseProposal instruction
+    };
+
+    build_and_send_transaction(svm, fee_payer, vec![close_ix])
+}
+
+pub fn update_multisig_data(update_type: u8, value: u64, threshold: u8) -> Vec<u8> {
+    [
+        vec![1], // discriminator for UpdateMultisig instruction
+        value.to_le_bytes().to_vec(),
+        vec![update_type, threshold],
+        vec![0; 6],
+    ]
+    .concat()
+}
+
+pub fn update_members_data(operation: u8, member: Pubkey, role: u8) -> Vec<u8> {
+    [
+        vec![7, operation], // discriminator for UpdateMembers instruction
+        member.to_bytes().to_vec(),
+        vec![role],
+        1u16.to_le_bytes().to_vec(), // weight
+        vec![0],                     // default permissions of the role
+    ]
+    .concat()
+}
+
+/// Payload of a transaction approving a config change: a single instruction to the
+/// multisig program carrying the same data as the config instruction
+pub fn config_payload(program_id: Pubkey, data: Vec<u8>) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![],
+        data,
+    }
+}
+
+pub fn update_multisig(
+    svm: &mut LiteSVM,
+    fee_payer: &Keypair,
+    program_id: Pubkey,
+    multisig_pda: Pubkey,
+    proposal_pda: Pubkey,
+    transaction_pda: Pubkey,
+    data: Vec<u8>,
+) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+    let update_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(fee_payer.pubkey(), true),
+            AccountMeta::new(multisig_pda, false),
+            AccountMeta::new(proposal_pda, false),
+            AccountMeta::new_readonly(transaction_pda, false),
+        ],
+        data,
+    };
+
+    build_and_send_transaction(svm, fee_payer, vec![update_ix])
+}
+
+pub fn update_members(
+    svm: &mut LiteSVM,
+    fee_payer: &Keypair,
+    program_id: Pubkey,
+    multisig_pda: Pubkey,
+    proposal_pda: Pubkey,
+    transaction_pda: Pubkey,
+    data: Vec<u8>,
+) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+    let update_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(fee_payer.pubkey(), true),
+            AccountMeta::new(multisig_pda, false),
+            AccountMeta::new(proposal_pda, false),
+            AccountMeta::new_readonly(transaction_pda, false),
+            AccountMeta::new_readonly(rent::ID, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data,
+    };
+
+    build_and_send_transaction(svm, fee_payer, vec![update_ix])
+}
+
+/// Creates a transaction carrying the config change `data` and a proposal for it, then
+/// votes yes with every admin. Returns the proposal and transaction PDAs.
+pub fn approve_config_change(
+    svm: &mut LiteSVM,
+    admins: &[&Keypair],
+    program_id: Pubkey,
+    multisig_pda: Pubkey,
+    data: Vec<u8>,
+    proposal_seed: u16,
+) -> (Pubkey, Pubkey) {
+    let pda_transaction = create_transaction(
+        svm,
+        admins[0],
+        program_id,
+        multisig_pda,
+        &[config_payload(program_id, data)],
+    );
+    let (pda_proposal, proposal_bump) = create_proposal_with_seed(
+        svm,
+        admins[0],
+        program_id,
+        multisig_pda,
+        pda_transaction,
+        proposal_seed,
+    );
+    activate_proposal(svm, admins[0], program_id, multisig_pda, pda_proposal);
+
+    for admin in admins {
+        vote(svm, admin, program_id, multisig_pda, 0, pda_proposal, proposal_bump, 1);
+    }
+
+    (pda_proposal, pda_transaction)
+}
+
+pub fn spend_from_treasury(
+    svm: &mut LiteSVM,
+    fee_payer: &Keypair,
+    program_id: Pubkey,
+    multisig_pda: Pubkey,
+    recipient: Pubkey,
+    amount: u64,
+) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+    let (pda_treasury, _) =
+        Pubkey::find_program_address(&[b"treasury", multisig_pda.as_ref()], &program_id);
+
+    let spend_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(fee_payer.pubkey(), true),
+            AccountMeta::new(multisig_pda, false),
+            AccountMeta::new(pda_treasury, false),
+            AccountMeta::new(recipient, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: [vec![10u8], amount.to_le_bytes().to_vec()].concat(), // discriminator for SpendFromTreasury instruction
+    };
+
+    build_and_send_transaction(svm, fee_payer, vec![spend_ix])
+}
+
+pub fn spending_limit_pda(program_id: Pubkey, multisig_pda: Pubkey, seed: u16) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"spending_limit", multisig_pda.as_ref(), &seed.to_le_bytes()],
+        &program_id,
+    )
+    .0
+}
+
+pub fn create_spending_limit_data(
+    amount: u64,
+    mint: Pubkey,
+    seed: u16,
+    period: u8,
+    vault_index: u8,
+    members: &[Pubkey],
+    destinations: &[Pubkey],
+) -> Vec<u8> {
+    [
+        vec![11], // discriminator for CreateSpendingLimit instruction
+        amount.to_le_bytes().to_vec(),
+        mint.to_bytes().to_vec(),
+        seed.to_le_bytes().to_vec(),
+        vec![period, members.len() as u8, destinations.len() as u8, vault_index],
+        vec![0; 2],
+        members.iter().flat_map(|member| member.to_bytes()).collect(),
+        destinations.iter().flat_map(|destination| destination.to_bytes()).collect(),
+    ]
+    .concat()
+}
+
+pub fn create_spending_limit(
+    svm: &mut LiteSVM,
+    fee_payer: &Keypair,
+    program_id: Pubkey,
+    multisig_pda: Pubkey,
+    proposal_pda: Pubkey,
+    transaction_pda: Pubkey,
+    spending_limit_pda: Pubkey,
+    data: Vec<u8>,
+) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+    let create_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(fee_payer.pubkey(), true),
+            AccountMeta::new_readonly(multisig_pda, false),
+            AccountMeta::new(proposal_pda, false),
+            AccountMeta::new_readonly(transaction_pda, false),
+            AccountMeta::new(spending_limit_pda, false),
+            AccountMeta::new_readonly(rent::ID, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data,
+    };
+
+    build_and_send_transaction(svm, fee_payer, vec![create_ix])
+}
+
+pub fn use_spending_limit(
+    svm: &mut LiteSVM,
+    fee_payer: &Keypair,
+    program_id: Pubkey,
+    multisig_pda: Pubkey,
+    spending_limit_pda: Pubkey,
+    destination: Pubkey,
+    amount: u64,
+) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+    let (pda_treasury, _) =
+        Pubkey::find_program_address(&[b"treasury", multisig_pda.as_ref()], &program_id);
+
+    let use_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(fee_payer.pubkey(), true),
+            AccountMeta::new_readonly(multisig_pda, false),
+            AccountMeta::new(spending_limit_pda, false),
+            AccountMeta::new(pda_treasury, false),
+            AccountMeta::new(destination, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: [vec![12u8], amount.to_le_bytes().to_vec()].concat(), // discriminator for UseSpendingLimit instruction
+    };
+
+    build_and_send_transaction(svm, fee_payer, vec![use_ix])
+}
+
+pub fn transaction_buffer_pda(
+    program_id: Pubkey,
+    multisig_pda: Pubkey,
+    creator: Pubkey,
+    seed: u16,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"transaction_buffer",
+            multisig_pda.as_ref(),
+            creator.as_ref(),
+            &seed.to_le_bytes(),
+        ],
+        &program_id,
+    )
+    .0
+}
+
+pub fn create_transaction_buffer(
+    svm: &mut LiteSVM,
+    creator: &Keypair,
+    program_id: Pubkey,
+    multisig_pda: Pubkey,
+    buffer_pda: Pubkey,
+    final_hash: [u8; 32],
+    final_size: u16,
+    seed: u16,
+    chunk: &[u8],
+) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+    let create_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(creator.pubkey(), true),
+            AccountMeta::new_readonly(multisig_pda, false),
+            AccountMeta::new(buffer_pda, false),
+            AccountMeta::new_readonly(rent::ID, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: [
+            vec![14], // discriminator for CreateTransactionBuffer instruction
+            final_hash.to_vec(),
+            final_size.to_le_bytes().to_vec(),
+            seed.to_le_bytes().to_vec(),
+            vec![0, 0], // vault_index, padding
+            chunk.to_vec(),
+        ]
+        .concat(),
+    };
+
+    build_and_send_transaction(svm, creator, vec![create_ix])
+}
+
+pub fn extend_transaction_buffer(
+    svm: &mut LiteSVM,
+    creator: &Keypair,
+    program_id: Pubkey,
+    multisig_pda: Pubkey,
+    buffer_pda: Pubkey,
+    chunk: &[u8],
+) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+    let extend_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(creator.pubkey(), true),
+            AccountMeta::new_readonly(multisig_pda, false),
+            AccountMeta::new(buffer_pda, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: [vec![15], chunk.to_vec()].concat(), // discriminator for ExtendTransactionBuffer instruction
+    };
+
+    build_and_send_transaction(svm, creator, vec![extend_ix])
+}
+
+pub fn finalize_transaction_buffer(
+    svm: &mut LiteSVM,
+    creator: &Keypair,
+    program_id: Pubkey,
+    multisig_pda: Pubkey,
+    buffer_pda: Pubkey,
+    transaction_pda: Pubkey,
+) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+    let finalize_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(creator.pubkey(), true),
+            AccountMeta::new(multisig_pda, false),
+            AccountMeta::new(buffer_pda, false),
+            AccountMeta::new(transaction_pda, false),
+            AccountMeta::new_readonly(rent::ID, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: vec![16], // discriminator for FinalizeTransactionBuffer instruction
+    };
+
+    build_and_send_transaction(svm, creator, vec![finalize_ix])
+}
+
+pub fn close_transaction_buffer(
+    svm: &mut LiteSVM,
+    creator: &Keypair,
+    program_id: Pubkey,
+    multisig_pda: Pubkey,
+    buffer_pda: Pubkey,
+) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+    let close_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(creator.pubkey(), true),
+            AccountMeta::new_readonly(multisig_pda, false),
+            AccountMeta::new(buffer_pda, false),
+        ],
+        data: vec![21], // discriminator for CloseTransactionBuffer instruction
+    };
+
+    build_and_send_transaction(svm, creator, vec![close_ix])
+}
+
+/// Batch PDA at the next transaction index of the multisig
+pub fn batch_pda(svm: &LiteSVM, program_id: Pubkey, multisig_pda: Pubkey) -> Pubkey {
+    let multisig_account = svm.get_account(&multisig_pda).unwrap();
+    let multisig_state: &MultisigState =
+        bytemuck::from_bytes(&multisig_account.data[..MultisigState::LEN]);
+    let transaction_index = multisig_state.transaction_index + 1;
+
+    Pubkey::find_program_address(
+        &[b"batch", multisig_pda.as_ref(), &transaction_index.to_le_bytes()],
+        &program_id,
+    )
+    .0
+}
+
+pub fn create_batch(
+    svm: &mut LiteSVM,
+    creator: &Keypair,
+    program_id: Pubkey,
+    multisig_pda: Pubkey,
+    vault_index: u8,
+) -> Pubkey {
+    let pda_batch = batch_pda(svm, program_id, multisig_pda);
+
+    let create_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(creator.pubkey(), true),
+            AccountMeta::new(multisig_pda, false),
+            AccountMeta::new(pda_batch, false),
+            AccountMeta::new_readonly(rent::ID, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: vec![17, vault_index], // discriminator for CreateBatch instruction
+    };
+
+    let result = build_and_send_transaction(svm, creator, vec![create_ix]);
+    assert!(result.is_ok());
+
+    pda_batch
+}
+
+pub fn add_batch_transaction(
+    svm: &mut LiteSVM,
+    creator: &Keypair,
+    program_id: Pubkey,
+    multisig_pda: Pubkey,
+    batch_pda: Pubkey,
+    payload: &[u8],
+) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+    let add_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(creator.pubkey(), true),
+            AccountMeta::new_readonly(multisig_pda, false),
+            AccountMeta::new(batch_pda, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: [vec![18], payload.to_vec()].concat(), // discriminator for AddBatchTransaction instruction
+    };
+
+    build_and_send_transaction(svm, creator, vec![add_ix])
+}
+
+/// Executes the next transaction of the batch, signed by the treasury
+pub fn execute_batch_transaction(
+    svm: &mut LiteSVM,
+    fee_payer: &Keypair,
+    program_id: Pubkey,
+    multisig_pda: Pubkey,
+    proposal_pda: Pubkey,
+    batch_pda: Pubkey,
+    remaining: Vec<AccountMeta>,
+) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+    let (pda_treasury, _) =
+        Pubkey::find_program_address(&[b"treasury", multisig_pda.as_ref()], &program_id);
+
+    let mut accounts = vec![
+        AccountMeta::new(fee_payer.pubkey(), true),
+        AccountMeta::new_readonly(multisig_pda, false),
+        AccountMeta::new(proposal_pda, false),
+        AccountMeta::new(batch_pda, false),
+        AccountMeta::new(pda_treasury, false),
+    ];
+    accounts.extend(remaining);
+
+    let execute_ix = Instruction {
+        program_id,
+        accounts,
+        data: vec![19], // discriminator for ExecuteBatchTransaction instruction
+    };
+
+    build_and_send_transaction(svm, fee_payer, vec![execute_ix])
+}
+
+/// Closes `transaction_pda`, `remaining` carries the proposal claiming it if any
+pub fn close_transaction(
+    svm: &mut LiteSVM,
+    fee_payer: &Keypair,
+    program_id: Pubkey,
+    multisig_pda: Pubkey,
+    transaction_pda: Pubkey,
+    rent_payer: Pubkey,
+    remaining: Vec<AccountMeta>,
+) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+    let mut accounts = vec![
+        AccountMeta::new(fee_payer.pubkey(), true),
+        AccountMeta::new_readonly(multisig_pda, false),
+        AccountMeta::new(transaction_pda, false),
+        AccountMeta::new(rent_payer, false),
+    ];
+    accounts.extend(remaining);
+
+    let close_ix = Instruction {
+        program_id,
+        accounts,
+        data: vec![20], // discriminator for CloseTransaction instruction
+    };
+
+    build_and_send_transaction(svm, fee_payer, vec![close_ix])
+}
@@ -0,0 +1,476 @@
+use pinocchio_multisig::error::MultisigError;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, InstructionError},
+    pubkey,
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    system_instruction, system_program,
+    transaction::TransactionError,
+};
+
+mod common;
+
+const ADDRESS_LOOKUP_TABLE_PROGRAM_ID: Pubkey = pubkey!("AddressLookupTab1e1111111111111111111111111");
+
+#[test]
+pub fn test_execute_transfer_from_treasury() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    let (pda_multisig, multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let (pda_treasury, _) =
+        Pubkey::find_program_address(&[b"treasury", pda_multisig.as_ref()], &program_id);
+    svm.airdrop(&pda_treasury, 10_000_000).unwrap();
+
+    let recipient = Pubkey::new_unique();
+    let transfer_ix = system_instruction::transfer(&pda_treasury, &recipient, 1_000_000);
+    let pda_transaction = common::create_transaction(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        &[transfer_ix],
+    );
+
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+    );
+    common::activate_proposal(&mut svm, &second_admin, program_id, pda_multisig, pda_proposal);
+
+    for voter in [&second_admin, &third_admin] {
+        common::vote(
+            &mut svm,
+            voter,
+            program_id,
+            pda_multisig,
+            multisig_bump,
+            pda_proposal,
+            proposal_bump,
+            1,
+        );
+    }
+
+    let remaining = vec![
+        AccountMeta::new(recipient, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+
+    // The proposal only approves the transaction it was created for
+    let other_transaction =
+        common::create_placeholder_transaction(&mut svm, &second_admin, program_id, pda_multisig);
+    let result = common::execute_transaction(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        other_transaction,
+        remaining.clone(),
+    );
+    assert!(result.is_err());
+
+    // Only members can execute
+    let outsider = Keypair::new();
+    svm.airdrop(&outsider.pubkey(), 100000000).unwrap();
+    let result = common::execute_transaction(
+        &mut svm,
+        &outsider,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_transaction,
+        remaining.clone(),
+    );
+    assert!(result.is_err());
+
+    let result = common::execute_transaction(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_transaction,
+        remaining.clone(),
+    );
+    println!("execute transaction result: {:?}", result);
+    assert!(result.is_ok());
+    assert_eq!(svm.get_account(&recipient).unwrap().lamports, 1_000_000);
+
+    // A proposal can only be executed once
+    svm.expire_blockhash();
+    let result = common::execute_transaction(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_transaction,
+        remaining,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+pub fn test_execute_below_threshold() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    let (pda_multisig, multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let (pda_treasury, _) =
+        Pubkey::find_program_address(&[b"treasury", pda_multisig.as_ref()], &program_id);
+    svm.airdrop(&pda_treasury, 10_000_000).unwrap();
+
+    let recipient = Pubkey::new_unique();
+    let transfer_ix = system_instruction::transfer(&pda_treasury, &recipient, 1_000_000);
+    let pda_transaction = common::create_transaction(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        &[transfer_ix],
+    );
+
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+    );
+    common::activate_proposal(&mut svm, &second_admin, program_id, pda_multisig, pda_proposal);
+
+    // Only one of the two required votes
+    common::vote(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        multisig_bump,
+        pda_proposal,
+        proposal_bump,
+        1,
+    );
+
+    let result = common::execute_transaction(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_transaction,
+        vec![
+            AccountMeta::new(recipient, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+    );
+    assert!(result.is_err());
+    assert!(svm.get_account(&recipient).is_none());
+}
+
+#[test]
+pub fn test_execute_transfer_from_vault() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    let (pda_multisig, multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    // Vaults other than the treasury only need to be funded
+    let (pda_vault, _) =
+        Pubkey::find_program_address(&[b"vault", pda_multisig.as_ref(), &[1]], &program_id);
+    svm.airdrop(&pda_vault, 10_000_000).unwrap();
+
+    let recipient = Pubkey::new_unique();
+    let transfer_ix = system_instruction::transfer(&pda_vault, &recipient, 1_000_000);
+    let pda_transaction = common::create_vault_transaction(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        &[transfer_ix],
+        1,
+    );
+
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+    );
+    common::activate_proposal(&mut svm, &second_admin, program_id, pda_multisig, pda_proposal);
+
+    for voter in [&second_admin, &third_admin] {
+        common::vote(
+            &mut svm,
+            voter,
+            program_id,
+            pda_multisig,
+            multisig_bump,
+            pda_proposal,
+            proposal_bump,
+            1,
+        );
+    }
+
+    let remaining = vec![
+        AccountMeta::new(recipient, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+
+    // The transaction is signed by vault 1, not the treasury
+    let result = common::execute_transaction(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_transaction,
+        remaining.clone(),
+    );
+    assert!(result.is_err());
+
+    let result = common::execute_vault_transaction(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_transaction,
+        pda_vault,
+        remaining,
+    );
+    println!("execute vault transaction result: {:?}", result);
+    assert!(result.is_ok());
+    assert_eq!(svm.get_account(&recipient).unwrap().lamports, 1_000_000);
+    assert_eq!(svm.get_account(&pda_vault).unwrap().lamports, 9_000_000);
+}
+
+/// Writes an address lookup table holding `addresses`, frozen unless `authority` is set
+fn set_lookup_table(
+    svm: &mut litesvm::LiteSVM,
+    table: Pubkey,
+    addresses: &[Pubkey],
+    owner: Pubkey,
+    deactivation_slot: u64,
+    authority: Option<Pubkey>,
+) {
+    let mut data = vec![0u8; 56];
+    data[..4].copy_from_slice(&1u32.to_le_bytes()); // ProgramState::LookupTable
+    data[4..12].copy_from_slice(&deactivation_slot.to_le_bytes()); // u64::MAX while active
+    if let Some(authority) = authority {
+        data[21] = 1; // Some
+        data[22..54].copy_from_slice(authority.as_ref());
+    }
+    for address in addresses {
+        data.extend_from_slice(address.as_ref());
+    }
+
+    let account = Account {
+        lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+        data,
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    };
+    svm.set_account(table, account).unwrap();
+}
+
+#[test]
+pub fn test_execute_with_address_lookup_table() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    let (pda_multisig, multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let (pda_treasury, _) =
+        Pubkey::find_program_address(&[b"treasury", pda_multisig.as_ref()], &program_id);
+    svm.airdrop(&pda_treasury, 20_000_000).unwrap();
+
+    // Payroll to recipients stored in a lookup table, referenced by index in the message
+    let recipients: Vec<Pubkey> = (0..10).map(|_| Pubkey::new_unique()).collect();
+    let lookup_table = Pubkey::new_unique();
+    set_lookup_table(
+        &mut svm,
+        lookup_table,
+        &recipients,
+        ADDRESS_LOOKUP_TABLE_PROGRAM_ID,
+        u64::MAX,
+        None,
+    );
+
+    let transfers: Vec<_> = recipients
+        .iter()
+        .map(|recipient| system_instruction::transfer(&pda_treasury, recipient, 1_000_000))
+        .collect();
+    let payload = common::encode_message(&transfers, &[(lookup_table, &recipients[..])]);
+    assert!(payload.len() < common::encode_instructions(&transfers).len());
+
+    let pda_transaction = common::create_transaction_from_payload(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        &payload,
+        0,
+    );
+
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+    );
+    common::activate_proposal(&mut svm, &second_admin, program_id, pda_multisig, pda_proposal);
+
+    for voter in [&second_admin, &third_admin] {
+        common::vote(
+            &mut svm,
+            voter,
+            program_id,
+            pda_multisig,
+            multisig_bump,
+            pda_proposal,
+            proposal_bump,
+            1,
+        );
+    }
+
+    let mut remaining: Vec<AccountMeta> = recipients
+        .iter()
+        .map(|recipient| AccountMeta::new(*recipient, false))
+        .collect();
+    remaining.push(AccountMeta::new_readonly(system_program::ID, false));
+    remaining.push(AccountMeta::new_readonly(lookup_table, false));
+
+    // Only tables owned by the address lookup table program are trusted
+    set_lookup_table(
+        &mut svm,
+        lookup_table,
+        &recipients,
+        system_program::ID,
+        u64::MAX,
+        None,
+    );
+    let result = common::execute_transaction(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_transaction,
+        remaining.clone(),
+    );
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::InvalidLookupTable as u32)
+        )
+    );
+
+    // Deactivated tables can be closed and replaced, they are rejected too
+    set_lookup_table(
+        &mut svm,
+        lookup_table,
+        &recipients,
+        ADDRESS_LOOKUP_TABLE_PROGRAM_ID,
+        0,
+        None,
+    );
+    svm.expire_blockhash();
+    let result = common::execute_transaction(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_transaction,
+        remaining.clone(),
+    );
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::InvalidLookupTable as u32)
+        )
+    );
+
+    // Tables that still have an authority can change after the vote, they are rejected too
+    set_lookup_table(
+        &mut svm,
+        lookup_table,
+        &recipients,
+        ADDRESS_LOOKUP_TABLE_PROGRAM_ID,
+        u64::MAX,
+        Some(Pubkey::new_unique()),
+    );
+    svm.expire_blockhash();
+    let result = common::execute_transaction(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_transaction,
+        remaining.clone(),
+    );
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::InvalidLookupTable as u32)
+        )
+    );
+
+    set_lookup_table(
+        &mut svm,
+        lookup_table,
+        &recipients,
+        ADDRESS_LOOKUP_TABLE_PROGRAM_ID,
+        u64::MAX,
+        None,
+    );
+    svm.expire_blockhash();
+    let result = common::execute_transaction(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_transaction,
+        remaining,
+    );
+    println!("execute with lookup table result: {:?}", result);
+    assert!(result.is_ok());
+
+    for recipient in &recipients {
+        assert_eq!(svm.get_account(recipient).unwrap().lamports, 1_000_000);
+    }
+    // The treasury was created with its rent-exempt minimum of 890_880 lamports
+    assert_eq!(svm.get_account(&pda_treasury).unwrap().lamports, 10_890_880);
+}
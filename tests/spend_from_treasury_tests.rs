@@ -0,0 +1,145 @@
+use pinocchio_multisig::error::MultisigError;
+use solana_sdk::{
+    clock::Clock,
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    transaction::TransactionError,
+};
+
+mod common;
+
+/// Creates a multisig of `admins` with a funded treasury and an admin spending limit
+/// of `spending_limit` lamports per day
+fn multisig_with_spending_limit(
+    svm: &mut litesvm::LiteSVM,
+    fee_payer: &Keypair,
+    admins: &[&Keypair],
+    program_id: Pubkey,
+    spending_limit: u64,
+) -> Pubkey {
+    let (pda_multisig, _) = common::create_multisig(
+        svm,
+        fee_payer,
+        program_id,
+        admins.iter().map(|admin| admin.pubkey()).collect(),
+    );
+
+    let (pda_treasury, _) =
+        Pubkey::find_program_address(&[b"treasury", pda_multisig.as_ref()], &program_id);
+    svm.airdrop(&pda_treasury, 10_000_000).unwrap();
+
+    let data = common::update_multisig_data(2, spending_limit, 0);
+    let (pda_proposal, pda_transaction) =
+        common::approve_config_change(svm, admins, program_id, pda_multisig, data.clone(), 0);
+    let result = common::update_multisig(
+        svm,
+        admins[0],
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_transaction,
+        data,
+    );
+    assert!(result.is_ok());
+
+    pda_multisig
+}
+
+#[test]
+pub fn test_spend_within_limit() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+
+    let pda_multisig = multisig_with_spending_limit(
+        &mut svm,
+        &fee_payer,
+        &[&second_admin, &third_admin],
+        program_id,
+        2_000_000,
+    );
+
+    // Transfers to a new account have to cover its rent-exempt minimum
+    let recipient = Pubkey::new_unique();
+    let result = common::spend_from_treasury(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        recipient,
+        1_200_000,
+    );
+    assert!(result.is_ok());
+    assert_eq!(svm.get_account(&recipient).unwrap().lamports, 1_200_000);
+
+    // The limit is shared by all admins for the whole period
+    let result = common::spend_from_treasury(
+        &mut svm,
+        &third_admin,
+        program_id,
+        pda_multisig,
+        recipient,
+        1_200_000,
+    );
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::SpendingLimitExceeded as u32)
+        )
+    );
+
+    // A day later the usage is reset
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp += 86_400;
+    svm.set_sysvar::<Clock>(&clock);
+    svm.expire_blockhash();
+
+    let result = common::spend_from_treasury(
+        &mut svm,
+        &third_admin,
+        program_id,
+        pda_multisig,
+        recipient,
+        1_200_000,
+    );
+    assert!(result.is_ok());
+    assert_eq!(svm.get_account(&recipient).unwrap().lamports, 2_400_000);
+}
+
+#[test]
+pub fn test_spend_by_non_admin_fails() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+
+    let pda_multisig = multisig_with_spending_limit(
+        &mut svm,
+        &fee_payer,
+        &[&second_admin, &third_admin],
+        program_id,
+        1_000_000,
+    );
+
+    let recipient = Pubkey::new_unique();
+    let result = common::spend_from_treasury(
+        &mut svm,
+        &fee_payer,
+        program_id,
+        pda_multisig,
+        recipient,
+        100_000,
+    );
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::NotAnAdmin as u32)
+        )
+    );
+    assert!(svm.get_account(&recipient).is_none());
+}
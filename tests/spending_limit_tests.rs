@@ -0,0 +1,199 @@
+use pinocchio_multisig::{error::MultisigError, helper::StateDefinition, state::SpendingLimitState};
+use solana_sdk::{
+    clock::Clock,
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    transaction::TransactionError,
+};
+
+mod common;
+
+/// Creates a multisig of `admins` with a funded treasury and a daily native SOL spending
+/// limit of 1_000_000 lamports for `members`, restricted to `destinations`
+fn multisig_with_spending_limit(
+    svm: &mut litesvm::LiteSVM,
+    fee_payer: &Keypair,
+    admins: &[&Keypair],
+    program_id: Pubkey,
+    members: &[Pubkey],
+    destinations: &[Pubkey],
+) -> (Pubkey, Pubkey) {
+    let (pda_multisig, _) = common::create_multisig(
+        svm,
+        fee_payer,
+        program_id,
+        admins.iter().map(|admin| admin.pubkey()).collect(),
+    );
+
+    let (pda_treasury, _) =
+        Pubkey::find_program_address(&[b"treasury", pda_multisig.as_ref()], &program_id);
+    svm.airdrop(&pda_treasury, 10_000_000).unwrap();
+
+    let data = common::create_spending_limit_data(
+        1_000_000,
+        Pubkey::default(),
+        0,
+        1,
+        0,
+        members,
+        destinations,
+    );
+    let (pda_proposal, pda_transaction) =
+        common::approve_config_change(svm, admins, program_id, pda_multisig, data.clone(), 0);
+
+    let pda_spending_limit = common::spending_limit_pda(program_id, pda_multisig, 0);
+    let result = common::create_spending_limit(
+        svm,
+        admins[0],
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_transaction,
+        pda_spending_limit,
+        data,
+    );
+    println!("create spending limit result: {:?}", result);
+    assert!(result.is_ok());
+
+    (pda_multisig, pda_spending_limit)
+}
+
+#[test]
+pub fn test_use_spending_limit() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+
+    let (pda_multisig, pda_spending_limit) = multisig_with_spending_limit(
+        &mut svm,
+        &fee_payer,
+        &[&second_admin, &third_admin],
+        program_id,
+        &[third_admin.pubkey()],
+        &[],
+    );
+
+    let spending_limit_account = svm.get_account(&pda_spending_limit).unwrap();
+    assert_eq!(spending_limit_account.data.len(), SpendingLimitState::LEN + 32);
+
+    // Funded so transfers below its rent-exempt minimum go through
+    let destination = Pubkey::new_unique();
+    svm.airdrop(&destination, 1_000_000).unwrap();
+
+    let result = common::use_spending_limit(
+        &mut svm,
+        &third_admin,
+        program_id,
+        pda_multisig,
+        pda_spending_limit,
+        destination,
+        700_000,
+    );
+    assert!(result.is_ok());
+    assert_eq!(svm.get_account(&destination).unwrap().lamports, 1_700_000);
+
+    svm.expire_blockhash();
+    let result = common::use_spending_limit(
+        &mut svm,
+        &third_admin,
+        program_id,
+        pda_multisig,
+        pda_spending_limit,
+        destination,
+        700_000,
+    );
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::SpendingLimitExceeded as u32)
+        )
+    );
+
+    // The amount is replenished every day
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp += 86_400;
+    svm.set_sysvar::<Clock>(&clock);
+    svm.expire_blockhash();
+
+    let result = common::use_spending_limit(
+        &mut svm,
+        &third_admin,
+        program_id,
+        pda_multisig,
+        pda_spending_limit,
+        destination,
+        700_000,
+    );
+    assert!(result.is_ok());
+    assert_eq!(svm.get_account(&destination).unwrap().lamports, 2_400_000);
+}
+
+#[test]
+pub fn test_use_spending_limit_restrictions() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+
+    let destination = Pubkey::new_unique();
+    let (pda_multisig, pda_spending_limit) = multisig_with_spending_limit(
+        &mut svm,
+        &fee_payer,
+        &[&second_admin, &third_admin],
+        program_id,
+        &[third_admin.pubkey()],
+        &[destination],
+    );
+    svm.airdrop(&destination, 1_000_000).unwrap();
+
+    // Members not listed on the spending limit still need a vote
+    let result = common::use_spending_limit(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_spending_limit,
+        destination,
+        100_000,
+    );
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::MemberNotAllowed as u32)
+        )
+    );
+
+    let other_destination = Pubkey::new_unique();
+    let result = common::use_spending_limit(
+        &mut svm,
+        &third_admin,
+        program_id,
+        pda_multisig,
+        pda_spending_limit,
+        other_destination,
+        100_000,
+    );
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::DestinationNotAllowed as u32)
+        )
+    );
+
+    let result = common::use_spending_limit(
+        &mut svm,
+        &third_admin,
+        program_id,
+        pda_multisig,
+        pda_spending_limit,
+        destination,
+        100_000,
+    );
+    assert!(result.is_ok());
+}
@@ -0,0 +1,290 @@
+use pinocchio_multisig::{error::MultisigError, helper::StateDefinition, state::TransactionState};
+use solana_sdk::{
+    hash::hash,
+    instruction::{AccountMeta, InstructionError},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    system_instruction, system_program,
+    transaction::TransactionError,
+};
+
+mod common;
+
+#[test]
+pub fn test_execute_transaction_from_buffer() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    let (pda_multisig, multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let (pda_treasury, _) =
+        Pubkey::find_program_address(&[b"treasury", pda_multisig.as_ref()], &program_id);
+    svm.airdrop(&pda_treasury, 20_000_000).unwrap();
+
+    // Fourteen transfers don't fit in the 512 bytes of CreateTransaction
+    let recipients: Vec<Pubkey> = (0..14).map(|_| Pubkey::new_unique()).collect();
+    let transfers: Vec<_> = recipients
+        .iter()
+        .map(|recipient| system_instruction::transfer(&pda_treasury, recipient, 1_000_000))
+        .collect();
+    let payload = common::encode_instructions(&transfers);
+    assert!(payload.len() > 512);
+
+    let pda_buffer = common::transaction_buffer_pda(program_id, pda_multisig, second_admin.pubkey(), 0);
+    let (first_chunk, second_chunk) = payload.split_at(400);
+
+    let result = common::create_transaction_buffer(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_buffer,
+        hash(&payload).to_bytes(),
+        payload.len() as u16,
+        0,
+        first_chunk,
+    );
+    println!("create transaction buffer result: {:?}", result);
+    assert!(result.is_ok());
+
+    let result = common::extend_transaction_buffer(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_buffer,
+        second_chunk,
+    );
+    println!("extend transaction buffer result: {:?}", result);
+    assert!(result.is_ok());
+
+    let pda_transaction = common::transaction_pda(&svm, program_id, pda_multisig);
+    let result = common::finalize_transaction_buffer(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_buffer,
+        pda_transaction,
+    );
+    println!("finalize transaction buffer result: {:?}", result);
+    assert!(result.is_ok());
+
+    // The buffer is closed and the payload stored after the transaction state
+    assert!(svm.get_account(&pda_buffer).map_or(true, |account| account.lamports == 0));
+    let transaction_account = svm.get_account(&pda_transaction).unwrap();
+    assert_eq!(transaction_account.data.len(), TransactionState::LEN + payload.len());
+    assert_eq!(&transaction_account.data[TransactionState::LEN..], &payload[..]);
+
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+    );
+    common::activate_proposal(&mut svm, &second_admin, program_id, pda_multisig, pda_proposal);
+
+    for voter in [&second_admin, &third_admin] {
+        common::vote(
+            &mut svm,
+            voter,
+            program_id,
+            pda_multisig,
+            multisig_bump,
+            pda_proposal,
+            proposal_bump,
+            1,
+        );
+    }
+
+    let mut remaining: Vec<AccountMeta> = recipients
+        .iter()
+        .map(|recipient| AccountMeta::new(*recipient, false))
+        .collect();
+    remaining.push(AccountMeta::new_readonly(system_program::ID, false));
+
+    let result = common::execute_transaction(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_transaction,
+        remaining,
+    );
+    println!("execute transaction result: {:?}", result);
+    assert!(result.is_ok());
+
+    for recipient in &recipients {
+        assert_eq!(svm.get_account(recipient).unwrap().lamports, 1_000_000);
+    }
+    // The treasury was created with its rent-exempt minimum of 890_880 lamports
+    assert_eq!(svm.get_account(&pda_treasury).unwrap().lamports, 6_890_880);
+}
+
+#[test]
+pub fn test_transaction_buffer_checks() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    let (pda_multisig, _) = common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let payload = vec![7u8; 600];
+    let pda_buffer = common::transaction_buffer_pda(program_id, pda_multisig, second_admin.pubkey(), 1);
+
+    // The hash doesn't match the uploaded payload
+    let result = common::create_transaction_buffer(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_buffer,
+        [1u8; 32],
+        payload.len() as u16,
+        1,
+        &payload[..300],
+    );
+    assert!(result.is_ok());
+
+    // Only the creator can extend the buffer
+    let result = common::extend_transaction_buffer(
+        &mut svm,
+        &third_admin,
+        program_id,
+        pda_multisig,
+        pda_buffer,
+        &payload[300..],
+    );
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::CreatorMismatch as u32)
+        )
+    );
+
+    // A batch of the same multisig and creator isn't a buffer
+    let pda_batch = common::create_batch(&mut svm, &second_admin, program_id, pda_multisig, 0);
+    let result = common::extend_transaction_buffer(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_batch,
+        &payload[300..],
+    );
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::PdaMismatch as u32)
+        )
+    );
+
+    // The payload can't grow past the size the buffer was created with
+    let result = common::extend_transaction_buffer(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_buffer,
+        &[7u8; 301],
+    );
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::PayloadTooLarge as u32)
+        )
+    );
+
+    let pda_transaction = common::transaction_pda(&svm, program_id, pda_multisig);
+
+    // Incomplete payload
+    let result = common::finalize_transaction_buffer(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_buffer,
+        pda_transaction,
+    );
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::InvalidPayload as u32)
+        )
+    );
+
+    let result = common::extend_transaction_buffer(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_buffer,
+        &payload[300..],
+    );
+    assert!(result.is_ok());
+
+    // Complete payload, wrong hash
+    svm.expire_blockhash();
+    let result = common::finalize_transaction_buffer(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_buffer,
+        pda_transaction,
+    );
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::InvalidPayload as u32)
+        )
+    );
+    assert!(svm.get_account(&pda_transaction).map_or(true, |account| account.data.is_empty()));
+
+    // The abandoned buffer can only be closed by its creator
+    let result = common::close_transaction_buffer(
+        &mut svm,
+        &third_admin,
+        program_id,
+        pda_multisig,
+        pda_buffer,
+    );
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::CreatorMismatch as u32)
+        )
+    );
+
+    let rent = svm.get_account(&pda_buffer).unwrap().lamports;
+    let creator_lamports = svm.get_account(&second_admin.pubkey()).unwrap().lamports;
+
+    let result = common::close_transaction_buffer(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_buffer,
+    );
+    assert!(result.is_ok());
+    assert!(svm.get_account(&pda_buffer).map_or(true, |account| account.lamports == 0));
+
+    // The creator paid the transaction fee but got the buffer rent back
+    let creator_after = svm.get_account(&second_admin.pubkey()).unwrap().lamports;
+    assert!(creator_after > creator_lamports + rent - 10_000);
+}
@@ -0,0 +1,268 @@
+use pinocchio_multisig::error::MultisigError;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey,
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    system_program,
+    transaction::TransactionError,
+};
+
+mod common;
+
+const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+const TOKEN_2022_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey = pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+fn associated_token_account(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[owner.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Writes an initialized mint without authorities
+fn set_mint(svm: &mut litesvm::LiteSVM, mint: Pubkey, decimals: u8, token_program: Pubkey) {
+    let mut data = vec![0u8; 82];
+    data[36..44].copy_from_slice(&1_000_000u64.to_le_bytes()); // supply
+    data[44] = decimals;
+    data[45] = 1; // is_initialized
+
+    let account = Account {
+        lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+        data,
+        owner: token_program,
+        executable: false,
+        rent_epoch: 0,
+    };
+    svm.set_account(mint, account).unwrap();
+}
+
+/// Writes an initialized token account holding `amount` tokens of `mint`
+fn set_token_account(
+    svm: &mut litesvm::LiteSVM,
+    address: Pubkey,
+    mint: Pubkey,
+    owner: Pubkey,
+    amount: u64,
+    token_program: Pubkey,
+) {
+    let mut data = vec![0u8; 165];
+    data[..32].copy_from_slice(mint.as_ref());
+    data[32..64].copy_from_slice(owner.as_ref());
+    data[64..72].copy_from_slice(&amount.to_le_bytes());
+    data[108] = 1; // AccountState::Initialized
+
+    let account = Account {
+        lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+        data,
+        owner: token_program,
+        executable: false,
+        rent_epoch: 0,
+    };
+    svm.set_account(address, account).unwrap();
+}
+
+fn token_amount(svm: &litesvm::LiteSVM, address: &Pubkey) -> u64 {
+    let account = svm.get_account(address).unwrap();
+    u64::from_le_bytes(account.data[64..72].try_into().unwrap())
+}
+
+/// Creates a multisig of `admins` whose treasury holds 1_000 tokens of a new mint with
+/// 6 decimals, and a destination token account. Returns the multisig, the mint, the
+/// treasury token account and the destination token account.
+fn multisig_with_tokens(
+    svm: &mut litesvm::LiteSVM,
+    fee_payer: &Keypair,
+    admins: &[&Keypair],
+    program_id: Pubkey,
+    token_program: Pubkey,
+) -> (Pubkey, Pubkey, Pubkey, Pubkey) {
+    let (pda_multisig, _) = common::create_multisig(
+        svm,
+        fee_payer,
+        program_id,
+        admins.iter().map(|admin| admin.pubkey()).collect(),
+    );
+    let (pda_treasury, _) =
+        Pubkey::find_program_address(&[b"treasury", pda_multisig.as_ref()], &program_id);
+
+    let mint = Pubkey::new_unique();
+    set_mint(svm, mint, 6, token_program);
+
+    let treasury_token_account = associated_token_account(&pda_treasury, &mint, &token_program);
+    set_token_account(svm, treasury_token_account, mint, pda_treasury, 1_000, token_program);
+
+    let destination = Pubkey::new_unique();
+    set_token_account(svm, destination, mint, Pubkey::new_unique(), 0, token_program);
+
+    (pda_multisig, mint, treasury_token_account, destination)
+}
+
+fn transfer_tokens_data(amount: u64, mint: Pubkey, destination: Pubkey, decimals: u8) -> Vec<u8> {
+    [
+        vec![13], // discriminator for TransferTokens instruction
+        amount.to_le_bytes().to_vec(),
+        mint.to_bytes().to_vec(),
+        destination.to_bytes().to_vec(),
+        vec![decimals],
+        vec![0; 7],
+    ]
+    .concat()
+}
+
+fn transfer_tokens(
+    svm: &mut litesvm::LiteSVM,
+    fee_payer: &Keypair,
+    program_id: Pubkey,
+    multisig_pda: Pubkey,
+    proposal_pda: Pubkey,
+    transaction_pda: Pubkey,
+    accounts: [Pubkey; 4],
+    data: Vec<u8>,
+) -> Result<litesvm::types::TransactionMetadata, litesvm::types::FailedTransactionMetadata> {
+    let [source, mint, destination, token_program] = accounts;
+    let (pda_treasury, _) =
+        Pubkey::find_program_address(&[b"treasury", multisig_pda.as_ref()], &program_id);
+
+    let transfer_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(fee_payer.pubkey(), true),
+            AccountMeta::new_readonly(multisig_pda, false),
+            AccountMeta::new(proposal_pda, false),
+            AccountMeta::new_readonly(transaction_pda, false),
+            AccountMeta::new_readonly(pda_treasury, false),
+            AccountMeta::new(source, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new(destination, false),
+            AccountMeta::new_readonly(token_program, false),
+        ],
+        data,
+    };
+
+    common::build_and_send_transaction(svm, fee_payer, vec![transfer_ix])
+}
+
+#[test]
+pub fn test_transfer_tokens_with_approved_proposal() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = [&second_admin, &third_admin];
+
+    let (pda_multisig, mint, source, destination) =
+        multisig_with_tokens(&mut svm, &fee_payer, &admins, program_id, TOKEN_PROGRAM_ID);
+
+    let data = transfer_tokens_data(400, mint, destination, 6);
+    let (pda_proposal, pda_transaction) =
+        common::approve_config_change(&mut svm, &admins, program_id, pda_multisig, data.clone(), 0);
+
+    let result = transfer_tokens(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_transaction,
+        [source, mint, destination, TOKEN_PROGRAM_ID],
+        data,
+    );
+    println!("transfer tokens result: {:?}", result);
+    assert!(result.is_ok());
+    assert_eq!(token_amount(&svm, &source), 600);
+    assert_eq!(token_amount(&svm, &destination), 400);
+
+    // The approved decimals have to match the mint
+    let data = transfer_tokens_data(400, mint, destination, 9);
+    let (pda_proposal, pda_transaction) =
+        common::approve_config_change(&mut svm, &admins, program_id, pda_multisig, data.clone(), 1);
+
+    let result = transfer_tokens(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_transaction,
+        [source, mint, destination, TOKEN_PROGRAM_ID],
+        data,
+    );
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::DecimalsMismatch as u32)
+        )
+    );
+    assert_eq!(token_amount(&svm, &destination), 400);
+}
+
+#[test]
+pub fn test_token_2022_spending_limit() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = [&second_admin, &third_admin];
+
+    let (pda_multisig, mint, source, destination) =
+        multisig_with_tokens(&mut svm, &fee_payer, &admins, program_id, TOKEN_2022_PROGRAM_ID);
+
+    // A one-time allowance of 500 tokens for the third admin
+    let data =
+        common::create_spending_limit_data(500, mint, 0, 0, 0, &[third_admin.pubkey()], &[]);
+    let (pda_proposal, pda_transaction) =
+        common::approve_config_change(&mut svm, &admins, program_id, pda_multisig, data.clone(), 0);
+
+    let pda_spending_limit = common::spending_limit_pda(program_id, pda_multisig, 0);
+    let result = common::create_spending_limit(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_transaction,
+        pda_spending_limit,
+        data,
+    );
+    assert!(result.is_ok());
+
+    let (pda_treasury, _) =
+        Pubkey::find_program_address(&[b"treasury", pda_multisig.as_ref()], &program_id);
+
+    let use_ix = |amount: u64| Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(third_admin.pubkey(), true),
+            AccountMeta::new_readonly(pda_multisig, false),
+            AccountMeta::new(pda_spending_limit, false),
+            AccountMeta::new_readonly(pda_treasury, false),
+            AccountMeta::new(destination, false),
+            AccountMeta::new_readonly(TOKEN_2022_PROGRAM_ID, false),
+            AccountMeta::new(source, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: [vec![12u8], amount.to_le_bytes().to_vec()].concat(),
+    };
+
+    let result = common::build_and_send_transaction(&mut svm, &third_admin, vec![use_ix(300)]);
+    println!("use spending limit result: {:?}", result);
+    assert!(result.is_ok());
+    assert_eq!(token_amount(&svm, &destination), 300);
+
+    let result = common::build_and_send_transaction(&mut svm, &third_admin, vec![use_ix(300)]);
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::SpendingLimitExceeded as u32)
+        )
+    );
+    assert_eq!(token_amount(&svm, &source), 700);
+}
@@ -1,18 +1,21 @@
 use litesvm::LiteSVM;
 use solana_sdk::{
-    instruction::{AccountMeta, Instruction},
+    instruction::{AccountMeta, Instruction, InstructionError},
     pubkey::{self, Pubkey},
     signature::Keypair,
     signer::Signer,
     system_program,
     sysvar::rent,
+    transaction::TransactionError,
 };
 
 use bytemuck::Pod;
 use pinocchio::account_info::AccountInfo;
-use pinocchio_multisig::{helper::account_init::StateDefinition, instructions::VoteIxData};
 use pinocchio_multisig::{
-    helper::to_bytes,
+    error::MultisigError, helper::account_init::StateDefinition, instructions::VoteIxData,
+};
+use pinocchio_multisig::{
+    helper::{hash, to_bytes},
     state::{
         proposal::{ProposalState, ProposalStatus},
         MemberState, MultisigState,
@@ -26,7 +29,6 @@ mod common;
 #[test]
 fn test_init_multisig_no_members() {
     let (mut svm, fee_payer, _second_admin, program_id) = common::setup_svm_and_program();
-    let fee_payer_pubkey = fee_payer.pubkey();
 
     let min_threshold: u8 = 2;
     let max_expiry: u64 = 1_000_000;
@@ -47,14 +49,14 @@ fn test_init_multisig_no_members() {
     // Multisig Config PDA
     let seed = [(b"multisig"), &primary_seed.to_le_bytes() as &[u8]];
     let seeds = &seed[..];
-    let (pda_multisig, multisig_bump) = Pubkey::find_program_address(seeds, &program_id);
+    let (pda_multisig, _multisig_bump) = Pubkey::find_program_address(seeds, &program_id);
 
     println!("pda_multisig acc : {:?}", pda_multisig);
 
     // Treasury PDA
     let treasury_seed = [(b"treasury"), pda_multisig.as_ref()];
     let treasury_seeds = &treasury_seed[..];
-    let (pda_treasury, treasury_bump) = Pubkey::find_program_address(treasury_seeds, &program_id);
+    let (pda_treasury, _treasury_bump) = Pubkey::find_program_address(treasury_seeds, &program_id);
 
     println!("pda_treasury acc : {:?}", pda_treasury);
 
@@ -73,20 +75,16 @@ fn test_init_multisig_no_members() {
 
     println!("result: {:?}", result);
 
-    assert!(result.is_ok());
-
-    // Verify the multisig state
-    let multisig_account = svm.get_account(&pda_multisig).unwrap();
-    let multisig_data = &multisig_account.data;
-    let multisig_state: &MultisigState = bytemuck::from_bytes(multisig_data);
-
-    // Check counters
-    assert_eq!(multisig_state.num_members, 0);
-    assert_eq!(multisig_state.admin_counter, 0);
-    assert_eq!(multisig_state.min_threshold, min_threshold);
-    assert_eq!(multisig_state.max_expiry, max_expiry);
+    // A threshold of 2 can never be reached without members
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::ThresholdUnreachable as u32)
+        )
+    );
 
-    println!("✅ Success: Multisig initialized with 0 members and 0 admins!");
+    println!("✅ Success: Multisig initialization correctly rejected an unreachable threshold!");
 }
 
 #[test]
@@ -246,6 +244,57 @@ fn test_init_multisig_all_admins() {
     println!("✅ Success: Multisig initialized with all members as admins!");
 }
 
+#[test]
+fn test_init_multisig_duplicate_members() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let min_threshold: u8 = 2;
+    let max_expiry: u64 = 1_000_000;
+    let num_members: u8 = 2;
+    let primary_seed: u16 = 2;
+    let num_admins: u8 = 2;
+
+    let data = [
+        vec![0], // discriminator for InitMultisig instruction
+        max_expiry.to_le_bytes().to_vec(),
+        primary_seed.to_le_bytes().to_vec(),
+        min_threshold.to_le_bytes().to_vec(),
+        num_members.to_le_bytes().to_vec(),
+        num_admins.to_le_bytes().to_vec(),
+        vec![0; 3], // 3 bytes of padding for 8-byte alignment
+    ]
+    .concat();
+
+    let seed = [(b"multisig"), &primary_seed.to_le_bytes() as &[u8]];
+    let (pda_multisig, _multisig_bump) = Pubkey::find_program_address(&seed, &program_id);
+    let treasury_seed = [(b"treasury"), pda_multisig.as_ref()];
+    let (pda_treasury, _treasury_bump) = Pubkey::find_program_address(&treasury_seed, &program_id);
+
+    // The same key passed twice would count its weight twice towards the thresholds
+    let instruction = vec![Instruction {
+        program_id: program_id,
+        accounts: vec![
+            AccountMeta::new(fee_payer.pubkey(), true),
+            AccountMeta::new(pda_multisig, false),
+            AccountMeta::new(pda_treasury, false),
+            AccountMeta::new(rent::ID, false),
+            AccountMeta::new(system_program::ID, false),
+            AccountMeta::new(second_admin.pubkey(), false),
+            AccountMeta::new(second_admin.pubkey(), false),
+        ],
+        data,
+    }];
+
+    let result = common::build_and_send_transaction(&mut svm, &fee_payer, instruction);
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::DuplicateMember as u32)
+        )
+    );
+}
+
 #[test]
 fn test_init_multisig_invalid_data() {
     let (mut svm, fee_payer, _second_admin, program_id) = common::setup_svm_and_program();
@@ -301,7 +350,7 @@ fn test_init_multisig_account_already_initialized() {
 
     let min_threshold: u8 = 1;
     let max_expiry: u64 = 1_000_000;
-    let num_members: u8 = 0;
+    let num_members: u8 = 1;
     let primary_seed: u16 = 5;
 
     let data = [
@@ -310,7 +359,7 @@ fn test_init_multisig_account_already_initialized() {
         primary_seed.to_le_bytes().to_vec(),
         min_threshold.to_le_bytes().to_vec(),
         num_members.to_le_bytes().to_vec(),
-        0u8.to_le_bytes().to_vec(),
+        1u8.to_le_bytes().to_vec(),
         vec![0; 3], // 3 bytes of padding for 8-byte alignment
     ]
     .concat();
@@ -334,6 +383,7 @@ fn test_init_multisig_account_already_initialized() {
             AccountMeta::new(pda_treasury, false),
             AccountMeta::new(rent::ID, false),
             AccountMeta::new(system_program::ID, false),
+            AccountMeta::new(fee_payer.pubkey(), false),
         ],
         data: data.clone(),
     }];
@@ -350,6 +400,7 @@ fn test_init_multisig_account_already_initialized() {
             AccountMeta::new(pda_treasury, false),
             AccountMeta::new(rent::ID, false),
             AccountMeta::new(system_program::ID, false),
+            AccountMeta::new(fee_payer.pubkey(), false),
         ],
         data,
     }];
@@ -360,61 +411,42 @@ fn test_init_multisig_account_already_initialized() {
     println!("✅ Success: Multisig initialization correctly rejected already initialized account!");
 }
 
-#[test]
-fn test_create_proposal() {
-    let (mut svm, fee_payer, _second_admin, program_id) = common::setup_svm_and_program();
-    let fee_payer_pubkey = fee_payer.pubkey();
-
-    let multisig_seed = [(b"multisig"), &0u16.to_le_bytes() as &[u8]];
-    let (pda_multisig, multisig_bump) = Pubkey::find_program_address(&multisig_seed, &program_id);
-
-    println!("pda_multisig acc : {:?}", pda_multisig);
-    println!("multisig_bump: {}", multisig_bump);
-
-    let min_threshold: u8 = 2;
-    let max_expiry: u64 = 1_000_000;
-    let num_members: u8 = 0; // No initial members for this test
-    let primary_seed: u16 = 0;
-
-    let data = [
-        vec![0], // discriminator for InitMultisig instruction
-        max_expiry.to_le_bytes().to_vec(),
-        primary_seed.to_le_bytes().to_vec(),
-        min_threshold.to_le_bytes().to_vec(),
-        num_members.to_le_bytes().to_vec(),
-        0u8.to_le_bytes().to_vec(),
-        vec![0; 3], // 3 bytes of padding for 8-byte alignment
-    ]
-    .concat();
-
-    // Multisig Config PDA
-    let seed = [(b"multisig"), &primary_seed.to_le_bytes() as &[u8]];
-    let seeds = &seed[..];
-    let (pda_multisig, multisig_bump) = Pubkey::find_program_address(seeds, &program_id);
-
-    println!("pda_multisig acc : {:?}", pda_multisig);
-
-    // Treasury PDA
-    let treasury_seed = [(b"treasury"), pda_multisig.as_ref()];
-    let treasury_seeds = &treasury_seed[..];
-    let (pda_treasury, treasury_bump) = Pubkey::find_program_address(treasury_seeds, &program_id);
-
-    println!("pda_treasury acc : {:?}", pda_treasury);
-
-    let instruction = vec![Instruction {
-        program_id: program_id,
+fn create_proposal_instruction(
+    creator: Pubkey,
+    pda_proposal: Pubkey,
+    pda_multisig: Pubkey,
+    pda_transaction: Pubkey,
+    program_id: Pubkey,
+    data: Vec<u8>,
+) -> Vec<Instruction> {
+    vec![Instruction {
+        program_id,
         accounts: vec![
-            AccountMeta::new(fee_payer.pubkey(), true),
-            AccountMeta::new(pda_multisig, false),
-            AccountMeta::new(pda_treasury, false),
-            AccountMeta::new(rent::ID, false),
-            AccountMeta::new(system_program::ID, false),
+            AccountMeta::new(creator, true),                 // creator (signer)
+            AccountMeta::new(pda_proposal, false),           // proposal_account (will be created)
+            AccountMeta::new_readonly(pda_multisig, false),  // multisig_account (readonly)
+            AccountMeta::new(pda_transaction, false),        // transaction approved by the proposal
+            AccountMeta::new_readonly(rent::ID, false),      // rent sysvar
+            AccountMeta::new_readonly(solana_sdk::sysvar::clock::ID, false), // clock sysvar
+            AccountMeta::new_readonly(system_program::ID, false), // system program
         ],
         data,
-    }];
-    let multisig_result = common::build_and_send_transaction(&mut svm, &fee_payer, instruction);
+    }]
+}
 
-    assert!(multisig_result.is_ok(), "Failed to create multisig");
+#[test]
+fn test_create_proposal() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let (pda_multisig, _multisig_bump) = common::create_multisig(
+        &mut svm,
+        &fee_payer,
+        program_id,
+        vec![fee_payer.pubkey(), second_admin.pubkey()],
+    );
+
+    let pda_transaction =
+        common::create_placeholder_transaction(&mut svm, &fee_payer, program_id, pda_multisig);
 
     let proposal_primary_seed: u16 = 1;
     let proposal_seed = [
@@ -437,18 +469,14 @@ fn test_create_proposal() {
     ]
     .concat();
 
-    let create_proposal_instruction = vec![Instruction {
+    let create_proposal_instruction = create_proposal_instruction(
+        fee_payer.pubkey(),
+        pda_proposal,
+        pda_multisig,
+        pda_transaction,
         program_id,
-        accounts: vec![
-            AccountMeta::new(fee_payer.pubkey(), true), // creator (signer)
-            AccountMeta::new(pda_proposal, false),      // proposal_account (will be created)
-            AccountMeta::new_readonly(pda_multisig, false), // multisig_account (readonly)
-            AccountMeta::new_readonly(rent::ID, false), // rent sysvar
-            AccountMeta::new_readonly(solana_sdk::sysvar::clock::ID, false), // clock sysvar
-            AccountMeta::new_readonly(system_program::ID, false), // system program
-        ],
-        data: create_proposal_data,
-    }];
+        create_proposal_data,
+    );
 
     // Send proposal creation transaction
     let result =
@@ -465,37 +493,122 @@ fn test_create_proposal() {
     // Read proposal state directly from bytes
     let proposal_data = &proposal_account.data;
     let proposal_id = u16::from_le_bytes([proposal_data[0], proposal_data[1]]);
-    let expiry = u64::from_le_bytes([
-        proposal_data[2],
-        proposal_data[3],
-        proposal_data[4],
-        proposal_data[5],
-        proposal_data[6],
-        proposal_data[7],
-        proposal_data[8],
-        proposal_data[9],
-    ]);
-    let created_time = u64::from_le_bytes([
-        proposal_data[10],
-        proposal_data[11],
-        proposal_data[12],
-        proposal_data[13],
-        proposal_data[14],
-        proposal_data[15],
-        proposal_data[16],
-        proposal_data[17],
-    ]);
+    let stored_expiry = u64::from_le_bytes(proposal_data[2..10].try_into().unwrap());
     let status = proposal_data[18]; // ProposalStatus as u8
     let bump = proposal_data[19];
+    let creator = &proposal_data[22..54];
+    let transaction = &proposal_data[54..86];
 
     // Verify proposal state fields
     assert_eq!(proposal_id, proposal_primary_seed);
-    assert_eq!(expiry, expiry);
+    assert_eq!(stored_expiry, expiry);
     assert_eq!(status, 0); // ProposalStatus::Draft = 0
+    assert_eq!(bump, proposal_bump);
+    assert_eq!(creator, fee_payer.pubkey().as_ref());
+    assert_eq!(transaction, pda_transaction.as_ref());
+
+    // The transaction now points back at the proposal
+    let transaction_account = svm.get_account(&pda_transaction).unwrap();
+    assert_eq!(&transaction_account.data[40..72], pda_proposal.as_ref());
 
     println!("✅ Success: Proposal created with correct state data!");
 }
 
+#[test]
+fn test_create_proposal_transaction_already_claimed() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let (pda_multisig, _multisig_bump) = common::create_multisig(
+        &mut svm,
+        &fee_payer,
+        program_id,
+        vec![fee_payer.pubkey(), second_admin.pubkey()],
+    );
+
+    let pda_transaction =
+        common::create_placeholder_transaction(&mut svm, &fee_payer, program_id, pda_multisig);
+    common::create_proposal(&mut svm, &fee_payer, program_id, pda_multisig, pda_transaction);
+
+    // A second proposal for the same transaction must be rejected
+    let proposal_primary_seed: u16 = 1;
+    let proposal_seed = [
+        b"proposal".as_ref(),
+        pda_multisig.as_ref(),
+        &proposal_primary_seed.to_le_bytes(),
+    ];
+    let (pda_proposal, _proposal_bump) = Pubkey::find_program_address(&proposal_seed, &program_id);
+
+    let create_proposal_data = [
+        vec![2],                                      // discriminator (CreateProposal)
+        1_000_000u64.to_le_bytes().to_vec(),          // expiry: u64 (8 bytes)
+        proposal_primary_seed.to_le_bytes().to_vec(), // primary_seed: u16 (2 bytes)
+        vec![0; 6],
+    ]
+    .concat();
+
+    let create_proposal_instruction = create_proposal_instruction(
+        fee_payer.pubkey(),
+        pda_proposal,
+        pda_multisig,
+        pda_transaction,
+        program_id,
+        create_proposal_data,
+    );
+
+    let result =
+        common::build_and_send_transaction(&mut svm, &fee_payer, create_proposal_instruction);
+    println!("create proposal for claimed transaction result: {:?}", result);
+    assert!(result.is_err(), "Expected error for an already claimed transaction");
+}
+
+#[test]
+fn test_create_proposal_expiry_out_of_range() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let (pda_multisig, _multisig_bump) = common::create_multisig(
+        &mut svm,
+        &fee_payer,
+        program_id,
+        vec![fee_payer.pubkey(), second_admin.pubkey()],
+    );
+
+    let pda_transaction =
+        common::create_placeholder_transaction(&mut svm, &fee_payer, program_id, pda_multisig);
+
+    let proposal_primary_seed: u16 = 0;
+    let proposal_seed = [
+        b"proposal".as_ref(),
+        pda_multisig.as_ref(),
+        &proposal_primary_seed.to_le_bytes(),
+    ];
+    let (pda_proposal, _proposal_bump) = Pubkey::find_program_address(&proposal_seed, &program_id);
+
+    // Already expired, and beyond `max_expiry` (1_000_000) from now
+    for expiry in [0u64, 1_000_001u64] {
+        let create_proposal_data = [
+            vec![2],                                      // discriminator (CreateProposal)
+            expiry.to_le_bytes().to_vec(),                // expiry: u64 (8 bytes)
+            proposal_primary_seed.to_le_bytes().to_vec(), // primary_seed: u16 (2 bytes)
+            vec![0; 6],
+        ]
+        .concat();
+
+        let create_proposal_instruction = create_proposal_instruction(
+            fee_payer.pubkey(),
+            pda_proposal,
+            pda_multisig,
+            pda_transaction,
+            program_id,
+            create_proposal_data,
+        );
+
+        let result =
+            common::build_and_send_transaction(&mut svm, &fee_payer, create_proposal_instruction);
+        println!("create proposal with expiry {} result: {:?}", expiry, result);
+        assert!(result.is_err(), "Expected error for an out of range expiry");
+    }
+}
+
 #[test]
 fn test_create_proposal_multisig_not_initialized() {
     let (mut svm, fee_payer, _second_admin, program_id) = common::setup_svm_and_program();
@@ -516,24 +629,20 @@ fn test_create_proposal_multisig_not_initialized() {
 
     let create_proposal_data = [
         vec![2],                                      // discriminator (CreateProposal)
-        0u64.to_le_bytes().to_vec(),                  // expiry: u64 (8 bytes)
+        1_000_000u64.to_le_bytes().to_vec(),          // expiry: u64 (8 bytes)
         proposal_primary_seed.to_le_bytes().to_vec(), // primary_seed: u16 (2 bytes)
         vec![0; 6],
     ]
     .concat();
 
-    let create_proposal_instruction = vec![Instruction {
+    let create_proposal_instruction = create_proposal_instruction(
+        fee_payer.pubkey(),
+        pda_proposal,
+        pda_multisig,
+        Pubkey::new_unique(),
         program_id,
-        accounts: vec![
-            AccountMeta::new(fee_payer.pubkey(), true),
-            AccountMeta::new(pda_proposal, false),
-            AccountMeta::new_readonly(pda_multisig, false),
-            AccountMeta::new_readonly(rent::ID, false),
-            AccountMeta::new_readonly(solana_sdk::sysvar::clock::ID, false), // clock sysvar
-            AccountMeta::new_readonly(system_program::ID, false),
-        ],
-        data: create_proposal_data,
-    }];
+        create_proposal_data,
+    );
 
     let result =
         common::build_and_send_transaction(&mut svm, &fee_payer, create_proposal_instruction);
@@ -546,43 +655,42 @@ fn test_create_proposal_multisig_not_initialized() {
 
 #[test]
 fn test_create_proposal_account_already_exists() {
-    let (mut svm, fee_payer, _second_admin, program_id) = common::setup_svm_and_program();
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
 
-    // Use the same multisig and proposal from the first test
-    let multisig_seed = [(b"multisig"), &0u16.to_le_bytes() as &[u8]];
-    let (pda_multisig, _multisig_bump) = Pubkey::find_program_address(&multisig_seed, &program_id);
+    let (pda_multisig, _multisig_bump) = common::create_multisig(
+        &mut svm,
+        &fee_payer,
+        program_id,
+        vec![fee_payer.pubkey(), second_admin.pubkey()],
+    );
 
-    // Use the same proposal primary seed as the first test
-    let proposal_primary_seed: u16 = 1;
-    let proposal_seed = [
-        b"proposal".as_ref(),
-        pda_multisig.as_ref(),
-        &proposal_primary_seed.to_le_bytes(),
-    ];
-    let (pda_proposal, _proposal_bump) = Pubkey::find_program_address(&proposal_seed, &program_id);
+    // The helper creates the proposal with primary seed 0
+    let first_transaction =
+        common::create_placeholder_transaction(&mut svm, &fee_payer, program_id, pda_multisig);
+    let (pda_proposal, _proposal_bump) =
+        common::create_proposal(&mut svm, &fee_payer, program_id, pda_multisig, first_transaction);
+
+    let second_transaction =
+        common::create_placeholder_transaction(&mut svm, &fee_payer, program_id, pda_multisig);
 
     let create_proposal_data = [
-        vec![2],                                      // discriminator (CreateProposal)
-        0u64.to_le_bytes().to_vec(),                  // expiry: u64 (8 bytes)
-        proposal_primary_seed.to_le_bytes().to_vec(), // primary_seed: u16 (2 bytes)
+        vec![2],                       // discriminator (CreateProposal)
+        1_000_000u64.to_le_bytes().to_vec(),   // expiry: u64 (8 bytes)
+        0u16.to_le_bytes().to_vec(),   // primary_seed: u16 (2 bytes)
         vec![0; 6],
     ]
     .concat();
 
-    let create_proposal_instruction = vec![Instruction {
+    let create_proposal_instruction = create_proposal_instruction(
+        fee_payer.pubkey(),
+        pda_proposal,
+        pda_multisig,
+        second_transaction,
         program_id,
-        accounts: vec![
-            AccountMeta::new(fee_payer.pubkey(), true),
-            AccountMeta::new(pda_proposal, false),
-            AccountMeta::new_readonly(pda_multisig, false),
-            AccountMeta::new_readonly(rent::ID, false),
-            AccountMeta::new_readonly(solana_sdk::sysvar::clock::ID, false),
-            AccountMeta::new_readonly(system_program::ID, false),
-        ],
-        data: create_proposal_data,
-    }];
+        create_proposal_data,
+    );
 
-    // Try to create the same proposal that already exists from the first test
+    // Try to create the same proposal again
     let result =
         common::build_and_send_transaction(&mut svm, &fee_payer, create_proposal_instruction);
     println!("create proposal with existing account result: {:?}", result);
@@ -613,24 +721,20 @@ fn test_create_proposal_invalid_multisig_owner() {
 
     let create_proposal_data = [
         vec![2],                                      // discriminator (CreateProposal)
-        0u64.to_le_bytes().to_vec(),                  // expiry: u64 (8 bytes)
+        1_000_000u64.to_le_bytes().to_vec(),          // expiry: u64 (8 bytes)
         proposal_primary_seed.to_le_bytes().to_vec(), // primary_seed: u16 (2 bytes)
         vec![0; 6],
     ]
     .concat();
 
-    let create_proposal_instruction = vec![Instruction {
+    let create_proposal_instruction = create_proposal_instruction(
+        fee_payer.pubkey(),
+        pda_proposal,
+        fake_multisig.pubkey(), // wrong owner
+        Pubkey::new_unique(),
         program_id,
-        accounts: vec![
-            AccountMeta::new(fee_payer.pubkey(), true),
-            AccountMeta::new(pda_proposal, false),
-            AccountMeta::new_readonly(fake_multisig.pubkey(), false), // wrong owner
-            AccountMeta::new_readonly(rent::ID, false),
-            AccountMeta::new_readonly(solana_sdk::sysvar::clock::ID, false),
-            AccountMeta::new_readonly(system_program::ID, false),
-        ],
-        data: create_proposal_data,
-    }];
+        create_proposal_data,
+    );
 
     let result =
         common::build_and_send_transaction(&mut svm, &fee_payer, create_proposal_instruction);
@@ -643,40 +747,17 @@ fn test_create_proposal_invalid_multisig_owner() {
 
 #[test]
 fn test_create_proposal_invalid_instruction_data() {
-    let (mut svm, fee_payer, _second_admin, program_id) = common::setup_svm_and_program();
-    let fee_payer_pubkey = fee_payer.pubkey();
-
-    let multisig_seed = [(b"multisig"), &3u16.to_le_bytes() as &[u8]];
-    let (pda_multisig, _multisig_bump) = Pubkey::find_program_address(&multisig_seed, &program_id);
-
-    let treasury_seed = [(b"treasury"), pda_multisig.as_ref()];
-    let (pda_treasury, _treasury_bump) = Pubkey::find_program_address(&treasury_seed, &program_id);
-
-    let init_data = [
-        vec![0], // discriminator
-        1_000_000u64.to_le_bytes().to_vec(),
-        3u16.to_le_bytes().to_vec(),
-        1u8.to_le_bytes().to_vec(),
-        0u8.to_le_bytes().to_vec(),
-        0u8.to_le_bytes().to_vec(),
-        vec![0; 3],
-    ]
-    .concat();
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
 
-    let init_instruction = vec![Instruction {
+    let (pda_multisig, _multisig_bump) = common::create_multisig(
+        &mut svm,
+        &fee_payer,
         program_id,
-        accounts: vec![
-            AccountMeta::new(fee_payer.pubkey(), true),
-            AccountMeta::new(pda_multisig, false),
-            AccountMeta::new(pda_treasury, false),
-            AccountMeta::new(rent::ID, false),
-            AccountMeta::new(system_program::ID, false),
-        ],
-        data: init_data,
-    }];
+        vec![fee_payer.pubkey(), second_admin.pubkey()],
+    );
 
-    let init_result = common::build_and_send_transaction(&mut svm, &fee_payer, init_instruction);
-    assert!(init_result.is_ok(), "Failed to initialize multisig");
+    let pda_transaction =
+        common::create_placeholder_transaction(&mut svm, &fee_payer, program_id, pda_multisig);
 
     // Use a primary seed for the proposal
     let proposal_primary_seed: u16 = 0;
@@ -690,18 +771,14 @@ fn test_create_proposal_invalid_instruction_data() {
     // Test with malformed data (too short - only discriminator, missing primary seed)
     let create_proposal_data = vec![2]; // Only discriminator, missing primary seed
 
-    let create_proposal_instruction = vec![Instruction {
+    let create_proposal_instruction = create_proposal_instruction(
+        fee_payer.pubkey(),
+        pda_proposal,
+        pda_multisig,
+        pda_transaction,
         program_id,
-        accounts: vec![
-            AccountMeta::new(fee_payer.pubkey(), true),
-            AccountMeta::new(pda_proposal, false),
-            AccountMeta::new_readonly(pda_multisig, false),
-            AccountMeta::new_readonly(rent::ID, false),
-            AccountMeta::new_readonly(solana_sdk::sysvar::clock::ID, false),
-            AccountMeta::new_readonly(system_program::ID, false),
-        ],
-        data: create_proposal_data,
-    }];
+        create_proposal_data,
+    );
 
     let result =
         common::build_and_send_transaction(&mut svm, &fee_payer, create_proposal_instruction);
@@ -711,40 +788,17 @@ fn test_create_proposal_invalid_instruction_data() {
 
 #[test]
 fn test_create_proposal_wrong_proposal_pda() {
-    let (mut svm, fee_payer, _second_admin, program_id) = common::setup_svm_and_program();
-    let fee_payer_pubkey = fee_payer.pubkey();
-
-    let multisig_seed = [(b"multisig"), &4u16.to_le_bytes() as &[u8]];
-    let (pda_multisig, _multisig_bump) = Pubkey::find_program_address(&multisig_seed, &program_id);
-
-    let treasury_seed = [(b"treasury"), pda_multisig.as_ref()];
-    let (pda_treasury, _treasury_bump) = Pubkey::find_program_address(&treasury_seed, &program_id);
-
-    let init_data = [
-        vec![0], // discriminator
-        1_000_000u64.to_le_bytes().to_vec(),
-        4u16.to_le_bytes().to_vec(),
-        1u8.to_le_bytes().to_vec(),
-        0u8.to_le_bytes().to_vec(),
-        0u8.to_le_bytes().to_vec(),
-        vec![0; 3],
-    ]
-    .concat();
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
 
-    let init_instruction = vec![Instruction {
+    let (pda_multisig, _multisig_bump) = common::create_multisig(
+        &mut svm,
+        &fee_payer,
         program_id,
-        accounts: vec![
-            AccountMeta::new(fee_payer.pubkey(), true),
-            AccountMeta::new(pda_multisig, false),
-            AccountMeta::new(pda_treasury, false),
-            AccountMeta::new(rent::ID, false),
-            AccountMeta::new(system_program::ID, false),
-        ],
-        data: init_data,
-    }];
+        vec![fee_payer.pubkey(), second_admin.pubkey()],
+    );
 
-    let init_result = common::build_and_send_transaction(&mut svm, &fee_payer, init_instruction);
-    assert!(init_result.is_ok(), "Failed to initialize multisig");
+    let pda_transaction =
+        common::create_placeholder_transaction(&mut svm, &fee_payer, program_id, pda_multisig);
 
     // Use wrong seeds for proposal PDA (missing primary seed)
     let wrong_proposal_seed = [b"proposal".as_ref(), pda_multisig.as_ref()]; // Missing primary seed
@@ -755,24 +809,20 @@ fn test_create_proposal_wrong_proposal_pda() {
     let proposal_primary_seed: u16 = 0;
     let create_proposal_data = [
         vec![2],                                      // discriminator (CreateProposal)
-        0u64.to_le_bytes().to_vec(),                  // expiry: u64 (8 bytes)
+        1_000_000u64.to_le_bytes().to_vec(),          // expiry: u64 (8 bytes)
         proposal_primary_seed.to_le_bytes().to_vec(), // primary_seed: u16 (2 bytes)
         vec![0; 6],
     ]
     .concat();
 
-    let create_proposal_instruction = vec![Instruction {
+    let create_proposal_instruction = create_proposal_instruction(
+        fee_payer.pubkey(),
+        wrong_pda_proposal, // wrong PDA
+        pda_multisig,
+        pda_transaction,
         program_id,
-        accounts: vec![
-            AccountMeta::new(fee_payer.pubkey(), true),
-            AccountMeta::new(wrong_pda_proposal, false), // wrong PDA
-            AccountMeta::new_readonly(pda_multisig, false),
-            AccountMeta::new_readonly(rent::ID, false),
-            AccountMeta::new_readonly(solana_sdk::sysvar::clock::ID, false),
-            AccountMeta::new_readonly(system_program::ID, false),
-        ],
-        data: create_proposal_data,
-    }];
+        create_proposal_data,
+    );
 
     let result =
         common::build_and_send_transaction(&mut svm, &fee_payer, create_proposal_instruction);
@@ -827,6 +877,11 @@ fn test_create_proposal_non_admin_member() {
     let init_result = common::build_and_send_transaction(&mut svm, &fee_payer, init_instruction);
     assert!(init_result.is_ok(), "Failed to initialize multisig");
 
+    // Normal members can't initiate by default, so the admin creates the transaction
+    svm.airdrop(&admin_member.pubkey(), 100000000).unwrap();
+    let pda_transaction =
+        common::create_placeholder_transaction(&mut svm, &admin_member, program_id, pda_multisig);
+
     // Try to create proposal with the normal member (second_admin) - should fail
     let proposal_primary_seed: u16 = 0;
     let proposal_seed = [
@@ -838,24 +893,20 @@ fn test_create_proposal_non_admin_member() {
 
     let create_proposal_data = [
         vec![2],                                      // discriminator (CreateProposal)
-        0u64.to_le_bytes().to_vec(),                  // expiry: u64 (8 bytes)
+        1_000_000u64.to_le_bytes().to_vec(),          // expiry: u64 (8 bytes)
         proposal_primary_seed.to_le_bytes().to_vec(), // primary_seed: u16 (2 bytes)
         vec![0; 6],
     ]
     .concat();
 
-    let create_proposal_instruction = vec![Instruction {
+    let create_proposal_instruction = create_proposal_instruction(
+        second_admin.pubkey(), // normal member as creator (signer)
+        pda_proposal,
+        pda_multisig,
+        pda_transaction,
         program_id,
-        accounts: vec![
-            AccountMeta::new(second_admin.pubkey(), true), // normal member as creator (signer)
-            AccountMeta::new(pda_proposal, false),         // proposal account
-            AccountMeta::new_readonly(pda_multisig, false), // multisig account
-            AccountMeta::new_readonly(rent::ID, false),    // rent sysvar
-            AccountMeta::new_readonly(solana_sdk::sysvar::clock::ID, false),
-            AccountMeta::new_readonly(system_program::ID, false), // system program
-        ],
-        data: create_proposal_data,
-    }];
+        create_proposal_data,
+    );
 
     let result =
         common::build_and_send_transaction(&mut svm, &second_admin, create_proposal_instruction);
@@ -866,43 +917,61 @@ fn test_create_proposal_non_admin_member() {
     );
 }
 
-#[test]
-fn test_create_transaction() {
-    let (mut svm, fee_payer, _second_admin, program_id) = common::setup_svm_and_program();
-
-    let transaction_index: u64 = 1;
-    let primary_seed: u16 = 10;
-    let buffer_size: u16 = 100;
-    let mut tx_buffer = [0u8; 512];
-    tx_buffer[..100].copy_from_slice(&[1u8; 100]); // Fill first 100 bytes with 1s
-
+fn create_transaction_instruction(
+    payer: Pubkey,
+    pda_multisig: Pubkey,
+    pda_transaction: Pubkey,
+    program_id: Pubkey,
+    tx_buffer: [u8; 512],
+    buffer_size: u16,
+) -> Vec<Instruction> {
     let data = [
         vec![5], // discriminator for CreateTransaction instruction
-        transaction_index.to_le_bytes().to_vec(),
-        primary_seed.to_le_bytes().to_vec(),
         tx_buffer.to_vec(),
         buffer_size.to_le_bytes().to_vec(),
-        vec![0; 4], // 4 bytes of padding for 8-byte alignment
+        vec![0, 0], // signed by the treasury
     ]
     .concat();
 
-    // Transaction PDA
-    let seed = [(b"transaction"), &primary_seed.to_le_bytes() as &[u8]];
-    let seeds = &seed[..];
-    let (pda_transaction, _) = Pubkey::find_program_address(seeds, &program_id);
-
-    let instruction = vec![Instruction {
+    vec![Instruction {
         program_id: program_id,
         accounts: vec![
-            AccountMeta::new(fee_payer.pubkey(), true),
+            AccountMeta::new(payer, true),
+            AccountMeta::new(pda_multisig, false),
             AccountMeta::new(pda_transaction, false),
             AccountMeta::new(rent::ID, false),
             AccountMeta::new(system_program::ID, false),
         ],
         data,
-    }];
+    }]
+}
 
-    let result = common::build_and_send_transaction(&mut svm, &fee_payer, instruction);
+#[test]
+fn test_create_transaction() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let (pda_multisig, _) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, vec![second_admin.pubkey()]);
+
+    // Payloads are `TransactionMessage`s, a single config change here
+    let (tx_buffer, buffer_size) =
+        common::serialize_instructions(&[common::config_payload(program_id, vec![1u8; 20])]);
+
+    // Transaction PDA, seeded by the multisig and the next transaction index
+    let seed = [(b"transaction"), pda_multisig.as_ref(), &1u64.to_le_bytes() as &[u8]];
+    let seeds = &seed[..];
+    let (pda_transaction, transaction_bump) = Pubkey::find_program_address(seeds, &program_id);
+
+    let instruction = create_transaction_instruction(
+        second_admin.pubkey(),
+        pda_multisig,
+        pda_transaction,
+        program_id,
+        tx_buffer,
+        buffer_size,
+    );
+
+    let result = common::build_and_send_transaction(&mut svm, &second_admin, instruction);
     println!("create transaction result: {:?}", result);
     assert!(result.is_ok());
 
@@ -912,71 +981,59 @@ fn test_create_transaction() {
 
     // Read transaction state directly from bytes
     let transaction_data = &transaction_account.data;
-    let tx_index = u64::from_le_bytes([
-        transaction_data[0],
-        transaction_data[1],
-        transaction_data[2],
-        transaction_data[3],
-        transaction_data[4],
-        transaction_data[5],
-        transaction_data[6],
-        transaction_data[7],
-    ]);
-    let buf_size = u16::from_le_bytes([transaction_data[8], transaction_data[9]]);
-    let bump = transaction_data[522]; // bump is at offset 8 + 2 + 512 = 522
+    let tx_index = u64::from_le_bytes(transaction_data[0..8].try_into().unwrap());
+    let multisig = &transaction_data[8..40];
+    let proposal = &transaction_data[40..72];
+    let buf_size = u16::from_le_bytes([transaction_data[72], transaction_data[73]]);
+    let bump = transaction_data[586]; // bump is at offset 8 + 32 + 32 + 2 + 512 = 586
 
     // Verify transaction state fields
-    assert_eq!(tx_index, transaction_index);
+    assert_eq!(tx_index, 1);
+    assert_eq!(multisig, pda_multisig.as_ref());
+    assert_eq!(proposal, &[0u8; 32]); // not claimed by a proposal yet
     assert_eq!(buf_size, buffer_size);
-    // Verify first 100 bytes of buffer are 1s
-    for i in 0..100 {
-        assert_eq!(transaction_data[10 + i], 1u8);
-    }
-    // Verify remaining buffer bytes are 0s
-    for i in 100..512 {
-        assert_eq!(transaction_data[10 + i], 0u8);
+    assert_eq!(bump, transaction_bump);
+    // Verify the buffer holds the message followed by 0s
+    let size = buffer_size as usize;
+    assert_eq!(&transaction_data[74..74 + size], &tx_buffer[..size]);
+    for i in size..512 {
+        assert_eq!(transaction_data[74 + i], 0u8);
     }
 
+    // The multisig hands out the next index
+    let multisig_account = svm.get_account(&pda_multisig).unwrap();
+    let multisig_state: &MultisigState =
+        bytemuck::from_bytes(&multisig_account.data[..MultisigState::LEN]);
+    assert_eq!(multisig_state.transaction_index, 1);
+
     println!("✅ Success: Transaction created with correct state data!");
 }
 
 #[test]
 fn test_create_transaction_max_buffer() {
-    let (mut svm, fee_payer, _second_admin, program_id) = common::setup_svm_and_program();
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
 
-    let transaction_index: u64 = 2;
-    let primary_seed: u16 = 20;
-    let buffer_size: u16 = 512; // Full buffer size
-    let mut tx_buffer = [0u8; 512];
-    tx_buffer.fill(0xFF); // Fill entire buffer with 0xFF
+    let (pda_multisig, _) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, vec![second_admin.pubkey()]);
 
-    let data = [
-        vec![5], // discriminator for CreateTransaction instruction
-        transaction_index.to_le_bytes().to_vec(),
-        primary_seed.to_le_bytes().to_vec(),
-        tx_buffer.to_vec(),
-        buffer_size.to_le_bytes().to_vec(),
-        vec![0; 4], // 4 bytes of padding for 8-byte alignment
-    ]
-    .concat();
+    // version, header and key (37) + num_instructions (1) + indexes (2) + data_len (2) + data
+    // + num_address_table_lookups (1)
+    let (tx_buffer, buffer_size) =
+        common::serialize_instructions(&[common::config_payload(program_id, vec![0xFF; 469])]);
+    assert_eq!(buffer_size, 512); // Full buffer size
 
-    // Transaction PDA
-    let seed = [(b"transaction"), &primary_seed.to_le_bytes() as &[u8]];
-    let seeds = &seed[..];
-    let (pda_transaction, _) = Pubkey::find_program_address(seeds, &program_id);
+    let pda_transaction = common::transaction_pda(&svm, program_id, pda_multisig);
 
-    let instruction = vec![Instruction {
-        program_id: program_id,
-        accounts: vec![
-            AccountMeta::new(fee_payer.pubkey(), true),
-            AccountMeta::new(pda_transaction, false),
-            AccountMeta::new(rent::ID, false),
-            AccountMeta::new(system_program::ID, false),
-        ],
-        data,
-    }];
+    let instruction = create_transaction_instruction(
+        second_admin.pubkey(),
+        pda_multisig,
+        pda_transaction,
+        program_id,
+        tx_buffer,
+        buffer_size,
+    );
 
-    let result = common::build_and_send_transaction(&mut svm, &fee_payer, instruction);
+    let result = common::build_and_send_transaction(&mut svm, &second_admin, instruction);
     println!("create transaction max buffer result: {:?}", result);
     assert!(result.is_ok());
 
@@ -989,107 +1046,172 @@ fn test_create_transaction_max_buffer() {
 
 #[test]
 fn test_create_transaction_empty_buffer() {
-    let (mut svm, fee_payer, _second_admin, program_id) = common::setup_svm_and_program();
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let (pda_multisig, _) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, vec![second_admin.pubkey()]);
 
-    let transaction_index: u64 = 3;
-    let primary_seed: u16 = 30;
     let buffer_size: u16 = 0; // Empty buffer
     let tx_buffer = [0u8; 512]; // All zeros
 
-    let data = [
-        vec![5], // discriminator for CreateTransaction instruction
-        transaction_index.to_le_bytes().to_vec(),
-        primary_seed.to_le_bytes().to_vec(),
-        tx_buffer.to_vec(),
-        buffer_size.to_le_bytes().to_vec(),
-        vec![0; 4], // 4 bytes of padding for 8-byte alignment
-    ]
-    .concat();
-
-    // Transaction PDA
-    let seed = [(b"transaction"), &primary_seed.to_le_bytes() as &[u8]];
-    let seeds = &seed[..];
-    let (pda_transaction, _) = Pubkey::find_program_address(seeds, &program_id);
+    let pda_transaction = common::transaction_pda(&svm, program_id, pda_multisig);
 
-    let instruction = vec![Instruction {
-        program_id: program_id,
-        accounts: vec![
-            AccountMeta::new(fee_payer.pubkey(), true),
-            AccountMeta::new(pda_transaction, false),
-            AccountMeta::new(rent::ID, false),
-            AccountMeta::new(system_program::ID, false),
-        ],
-        data,
-    }];
+    let instruction = create_transaction_instruction(
+        second_admin.pubkey(),
+        pda_multisig,
+        pda_transaction,
+        program_id,
+        tx_buffer,
+        buffer_size,
+    );
 
-    let result = common::build_and_send_transaction(&mut svm, &fee_payer, instruction);
+    let result = common::build_and_send_transaction(&mut svm, &second_admin, instruction);
     println!("create transaction empty buffer result: {:?}", result);
-    assert!(result.is_ok());
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::InvalidPayload as u32)
+        )
+    );
 
-    // Verify the transaction account was created
-    let transaction_account = svm.get_account(&pda_transaction).unwrap();
-    assert!(!transaction_account.data.is_empty());
+    // An empty payload is not a message
+    assert!(svm.get_account(&pda_transaction).map_or(true, |account| account.data.is_empty()));
 
-    println!("✅ Success: Transaction with empty buffer created successfully!");
+    println!("✅ Success: Transaction with empty buffer correctly rejected!");
 }
 
 #[test]
-fn test_create_transaction_account_already_initialized() {
-    let (mut svm, fee_payer, _second_admin, program_id) = common::setup_svm_and_program();
+fn test_create_transaction_malformed_payload() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
 
-    let transaction_index: u64 = 4;
-    let primary_seed: u16 = 40;
-    let buffer_size: u16 = 50;
-    let mut tx_buffer = [0u8; 512];
-    tx_buffer[..50].copy_from_slice(&[0xABu8; 50]); // Fill first 50 bytes with 0xAB
+    let (pda_multisig, _) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, vec![second_admin.pubkey()]);
 
-    let data = [
-        vec![5], // discriminator for CreateTransaction instruction
-        transaction_index.to_le_bytes().to_vec(),
-        primary_seed.to_le_bytes().to_vec(),
-        tx_buffer.to_vec(),
-        buffer_size.to_le_bytes().to_vec(),
-        vec![0; 4], // 4 bytes of padding for 8-byte alignment
-    ]
-    .concat();
+    let pda_transaction = common::transaction_pda(&svm, program_id, pda_multisig);
 
-    // Transaction PDA
-    let seed = [(b"transaction"), &primary_seed.to_le_bytes() as &[u8]];
-    let seeds = &seed[..];
-    let (pda_transaction, _) = Pubkey::find_program_address(seeds, &program_id);
+    let (valid_buffer, valid_size) =
+        common::serialize_instructions(&[common::config_payload(program_id, vec![1u8; 20])]);
+
+    let mut unknown_version = valid_buffer;
+    unknown_version[0] = 1;
+
+    // The program id index points past the account keys
+    let mut bad_index = valid_buffer;
+    bad_index[38] = 1;
+
+    let malformed = [
+        (unknown_version, valid_size),
+        (bad_index, valid_size),
+        (valid_buffer, valid_size - 1), // truncated
+        (valid_buffer, valid_size + 1), // trailing byte
+    ];
+
+    for (tx_buffer, buffer_size) in malformed {
+        svm.expire_blockhash();
+
+        let instruction = create_transaction_instruction(
+            second_admin.pubkey(),
+            pda_multisig,
+            pda_transaction,
+            program_id,
+            tx_buffer,
+            buffer_size,
+        );
+
+        let result = common::build_and_send_transaction(&mut svm, &second_admin, instruction);
+        assert_eq!(
+            result.unwrap_err().err,
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(MultisigError::InvalidPayload as u32)
+            )
+        );
+    }
+
+    println!("✅ Success: Malformed transaction payloads rejected at creation!");
+}
+
+#[test]
+fn test_create_transaction_stale_index() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let (pda_multisig, _) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, vec![second_admin.pubkey()]);
+
+    let (tx_buffer, buffer_size) =
+        common::serialize_instructions(&[common::config_payload(program_id, vec![0xABu8; 10])]);
+
+    let pda_transaction = common::transaction_pda(&svm, program_id, pda_multisig);
 
     // First creation - should succeed
-    let instruction1 = vec![Instruction {
-        program_id: program_id,
-        accounts: vec![
-            AccountMeta::new(fee_payer.pubkey(), true),
-            AccountMeta::new(pda_transaction, false),
-            AccountMeta::new(rent::ID, false),
-            AccountMeta::new(system_program::ID, false),
-        ],
-        data: data.clone(),
-    }];
+    let instruction1 = create_transaction_instruction(
+        second_admin.pubkey(),
+        pda_multisig,
+        pda_transaction,
+        program_id,
+        tx_buffer,
+        buffer_size,
+    );
 
-    let result1 = common::build_and_send_transaction(&mut svm, &fee_payer, instruction1);
+    let result1 = common::build_and_send_transaction(&mut svm, &second_admin, instruction1);
     assert!(result1.is_ok());
 
-    // Second creation - should fail
-    let instruction2 = vec![Instruction {
-        program_id: program_id,
-        accounts: vec![
-            AccountMeta::new(fee_payer.pubkey(), true),
-            AccountMeta::new(pda_transaction, false),
-            AccountMeta::new(rent::ID, false),
-            AccountMeta::new(system_program::ID, false),
-        ],
-        data,
-    }];
+    svm.expire_blockhash();
 
-    let result2 = common::build_and_send_transaction(&mut svm, &fee_payer, instruction2);
+    // Second creation with the same index - should fail
+    let instruction2 = create_transaction_instruction(
+        second_admin.pubkey(),
+        pda_multisig,
+        pda_transaction,
+        program_id,
+        tx_buffer,
+        buffer_size,
+    );
+
+    let result2 = common::build_and_send_transaction(&mut svm, &second_admin, instruction2);
     println!("create transaction duplicate result: {:?}", result2);
-    assert!(result2.is_err()); // Should fail because account is already initialized
+    assert!(result2.is_err()); // Should fail because the index was already used
+
+    println!("✅ Success: Transaction creation correctly rejected an already used index!");
+}
 
-    println!("✅ Success: Transaction creation correctly rejected already initialized account!");
+#[test]
+fn test_create_transaction_non_member() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let (pda_multisig, _) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, vec![second_admin.pubkey()]);
+
+    let pda_transaction = common::transaction_pda(&svm, program_id, pda_multisig);
+
+    // fee_payer created the multisig but is not one of its members
+    let instruction = create_transaction_instruction(
+        fee_payer.pubkey(),
+        pda_multisig,
+        pda_transaction,
+        program_id,
+        [0u8; 512],
+        0,
+    );
+
+    let result = common::build_and_send_transaction(&mut svm, &fee_payer, instruction);
+    println!("create transaction non member result: {:?}", result);
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::NotAMember as u32)
+        )
+    );
+}
+
+#[test]
+fn test_hash_is_sha256() {
+    // Host builds must hash like the sol_sha256 syscall
+    let payload = [1u8, 2, 3, 4];
+    assert_eq!(hash(&payload), solana_sdk::hash::hashv(&[&payload]).to_bytes());
+    assert_ne!(hash(&payload), hash(&[]));
 }
 
 // #[test]
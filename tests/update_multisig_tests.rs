@@ -0,0 +1,559 @@
+use pinocchio_multisig::{
+    error::MultisigError,
+    helper::StateDefinition,
+    state::{MemberState, MultisigState, Permission},
+};
+use solana_sdk::{
+    clock::Clock,
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    transaction::TransactionError,
+};
+
+mod common;
+
+/// Creates a multisig owned by `admins`, a transaction carrying `payload` and a
+/// proposal for it approved by every admin
+fn approved_proposal(
+    svm: &mut litesvm::LiteSVM,
+    fee_payer: &Keypair,
+    admins: &[&Keypair],
+    program_id: Pubkey,
+    payload: Instruction,
+) -> (Pubkey, Pubkey, Pubkey) {
+    let (pda_multisig, multisig_bump) = common::create_multisig(
+        svm,
+        fee_payer,
+        program_id,
+        admins.iter().map(|admin| admin.pubkey()).collect(),
+    );
+
+    let pda_transaction =
+        common::create_transaction(svm, admins[0], program_id, pda_multisig, &[payload]);
+    let (pda_proposal, proposal_bump) =
+        common::create_proposal(svm, admins[0], program_id, pda_multisig, pda_transaction);
+    common::activate_proposal(svm, admins[0], program_id, pda_multisig, pda_proposal);
+
+    for admin in admins {
+        common::vote(
+            svm,
+            admin,
+            program_id,
+            pda_multisig,
+            multisig_bump,
+            pda_proposal,
+            proposal_bump,
+            1,
+        );
+    }
+
+    (pda_multisig, pda_proposal, pda_transaction)
+}
+
+#[test]
+pub fn test_update_threshold_without_approval_fails() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    let (pda_multisig, _multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let data = common::update_multisig_data(1, 0, 1);
+    let payload = common::config_payload(program_id, data.clone());
+    let pda_transaction =
+        common::create_transaction(&mut svm, &second_admin, program_id, pda_multisig, &[payload]);
+    let (pda_proposal, _proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+    );
+
+    let result = common::update_multisig(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_transaction,
+        data,
+    );
+    assert!(result.is_err());
+
+    let multisig_account = svm.get_account(&pda_multisig).unwrap();
+    let multisig_state: &MultisigState =
+        bytemuck::from_bytes(&multisig_account.data[..MultisigState::LEN]);
+    assert_eq!(multisig_state.min_threshold, 2);
+}
+
+#[test]
+pub fn test_update_threshold_with_approved_proposal() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+
+    let data = common::update_multisig_data(1, 0, 1);
+    let (pda_multisig, pda_proposal, pda_transaction) = approved_proposal(
+        &mut svm,
+        &fee_payer,
+        &[&second_admin, &third_admin],
+        program_id,
+        common::config_payload(program_id, data.clone()),
+    );
+
+    let result = common::update_multisig(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_transaction,
+        data.clone(),
+    );
+    println!("update threshold result: {:?}", result);
+    assert!(result.is_ok());
+
+    let multisig_account = svm.get_account(&pda_multisig).unwrap();
+    let multisig_state: &MultisigState =
+        bytemuck::from_bytes(&multisig_account.data[..MultisigState::LEN]);
+    assert_eq!(multisig_state.min_threshold, 1);
+
+    // The proposal is consumed by the update
+    svm.expire_blockhash();
+    let result = common::update_multisig(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_transaction,
+        data,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+pub fn test_update_with_different_payload_fails() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+
+    // Approved a spending limit change, tries to change the threshold instead
+    let (pda_multisig, pda_proposal, pda_transaction) = approved_proposal(
+        &mut svm,
+        &fee_payer,
+        &[&second_admin, &third_admin],
+        program_id,
+        common::config_payload(program_id, common::update_multisig_data(2, 500, 0)),
+    );
+
+    let result = common::update_multisig(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_transaction,
+        common::update_multisig_data(1, 0, 1),
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+pub fn test_add_member_with_approved_proposal() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+
+    let new_member = Pubkey::new_unique();
+    let data = common::update_members_data(1, new_member, 0);
+    let (pda_multisig, pda_proposal, pda_transaction) = approved_proposal(
+        &mut svm,
+        &fee_payer,
+        &[&second_admin, &third_admin],
+        program_id,
+        common::config_payload(program_id, data.clone()),
+    );
+
+    let result = common::update_members(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_transaction,
+        data,
+    );
+    println!("add member result: {:?}", result);
+    assert!(result.is_ok());
+
+    let multisig_account = svm.get_account(&pda_multisig).unwrap();
+    let multisig_state: &MultisigState =
+        bytemuck::from_bytes(&multisig_account.data[..MultisigState::LEN]);
+    assert_eq!(multisig_state.num_members, 3);
+    assert_eq!(multisig_state.admin_counter, 2);
+    assert_eq!(
+        &multisig_account.data
+            [MultisigState::LEN + 2 * MemberState::LEN..MultisigState::LEN + 2 * MemberState::LEN + 32],
+        new_member.as_ref()
+    );
+}
+
+#[test]
+pub fn test_config_change_invalidates_in_flight_proposals() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+
+    let data = common::update_multisig_data(1, 0, 1);
+    let (pda_multisig, pda_proposal, pda_transaction) = approved_proposal(
+        &mut svm,
+        &fee_payer,
+        &[&second_admin, &third_admin],
+        program_id,
+        common::config_payload(program_id, data.clone()),
+    );
+
+    // A second proposal opened under the old threshold
+    let pending_transaction =
+        common::create_placeholder_transaction(&mut svm, &second_admin, program_id, pda_multisig);
+    let (pending_proposal, _) = common::create_proposal_with_seed(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pending_transaction,
+        1,
+    );
+    common::activate_proposal(&mut svm, &second_admin, program_id, pda_multisig, pending_proposal);
+
+    let result = common::update_multisig(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_transaction,
+        data,
+    );
+    assert!(result.is_ok());
+
+    let multisig_account = svm.get_account(&pda_multisig).unwrap();
+    let multisig_state: &MultisigState =
+        bytemuck::from_bytes(&multisig_account.data[..MultisigState::LEN]);
+    assert_eq!(
+        multisig_state.stale_transaction_index,
+        multisig_state.transaction_index
+    );
+
+    // Voting on the pending proposal is no longer possible
+    let vote_data = [vec![3u8], vec![0, 0, 1]].concat();
+    let vote_ix = solana_sdk::instruction::Instruction {
+        program_id,
+        accounts: vec![
+            solana_sdk::instruction::AccountMeta::new(third_admin.pubkey(), true),
+            solana_sdk::instruction::AccountMeta::new(pda_multisig, false),
+            solana_sdk::instruction::AccountMeta::new(pending_proposal, false),
+        ],
+        data: vote_data,
+    };
+    let result = common::build_and_send_transaction(&mut svm, &third_admin, vec![vote_ix]);
+    assert!(result.is_err());
+}
+
+#[test]
+pub fn test_cancel_threshold_change_invalidates_in_flight_proposals() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+
+    let data = common::update_multisig_data(5, 0, 1);
+    let (pda_multisig, pda_proposal, pda_transaction) = approved_proposal(
+        &mut svm,
+        &fee_payer,
+        &[&second_admin, &third_admin],
+        program_id,
+        common::config_payload(program_id, data.clone()),
+    );
+
+    // A second proposal opened under the old cancel threshold
+    let pending_transaction =
+        common::create_placeholder_transaction(&mut svm, &second_admin, program_id, pda_multisig);
+    let (pending_proposal, _) = common::create_proposal_with_seed(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pending_transaction,
+        1,
+    );
+    common::activate_proposal(&mut svm, &second_admin, program_id, pda_multisig, pending_proposal);
+
+    let result = common::update_multisig(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_transaction,
+        data,
+    );
+    assert!(result.is_ok());
+
+    let multisig_account = svm.get_account(&pda_multisig).unwrap();
+    let multisig_state: &MultisigState =
+        bytemuck::from_bytes(&multisig_account.data[..MultisigState::LEN]);
+    assert_eq!(multisig_state.cancel_threshold, 1);
+    assert_eq!(
+        multisig_state.stale_transaction_index,
+        multisig_state.transaction_index
+    );
+
+    // The pending proposal was invalidated, it can't be voted on anymore
+    let vote_ix = Instruction {
+        program_id,
+        accounts: vec![
+            solana_sdk::instruction::AccountMeta::new(third_admin.pubkey(), true),
+            solana_sdk::instruction::AccountMeta::new(pda_multisig, false),
+            solana_sdk::instruction::AccountMeta::new(pending_proposal, false),
+        ],
+        data: vec![3, 0, 0, 1],
+    };
+    let result = common::build_and_send_transaction(&mut svm, &third_admin, vec![vote_ix]);
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::ProposalStale as u32)
+        )
+    );
+}
+
+#[test]
+pub fn test_remove_member_below_threshold_fails() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+
+    // Two members with a threshold of two, removing one would brick the multisig
+    let data = common::update_members_data(2, third_admin.pubkey(), 0);
+    let (pda_multisig, pda_proposal, pda_transaction) = approved_proposal(
+        &mut svm,
+        &fee_payer,
+        &[&second_admin, &third_admin],
+        program_id,
+        common::config_payload(program_id, data.clone()),
+    );
+
+    let result = common::update_members(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_transaction,
+        data,
+    );
+    println!("remove member result: {:?}", result);
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::ThresholdUnreachable as u32)
+        )
+    );
+
+    let multisig_account = svm.get_account(&pda_multisig).unwrap();
+    let multisig_state: &MultisigState =
+        bytemuck::from_bytes(&multisig_account.data[..MultisigState::LEN]);
+    assert_eq!(multisig_state.num_members, 2);
+}
+
+#[test]
+pub fn test_threshold_counts_only_voters() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+
+    let unreachable = TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(MultisigError::ThresholdUnreachable as u32),
+    );
+
+    // Two members with a threshold of two, but only one of them can vote
+    let result = common::try_create_weighted_multisig(
+        &mut svm,
+        &fee_payer,
+        program_id,
+        vec![second_admin.pubkey(), third_admin.pubkey()],
+        &[],
+        &[Permission::Initiate as u8, MemberState::ALL_PERMISSIONS],
+        0,
+    );
+    assert_eq!(result.unwrap_err().err, unreachable);
+
+    // A member added without the vote permission doesn't count towards the thresholds
+    let mut data = common::update_members_data(1, Pubkey::new_unique(), 0);
+    *data.last_mut().unwrap() = Permission::Initiate as u8;
+    let (pda_multisig, pda_proposal, pda_transaction) = approved_proposal(
+        &mut svm,
+        &fee_payer,
+        &[&second_admin, &third_admin],
+        program_id,
+        common::config_payload(program_id, data.clone()),
+    );
+
+    let result = common::update_members(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_transaction,
+        data,
+    );
+    assert!(result.is_ok());
+
+    // Three members, two of them voters with a weight of one each
+    for (data, proposal_seed) in [
+        (common::update_multisig_data(1, 0, 3), 1),
+        (common::update_multisig_data(5, 0, 3), 2),
+        (common::update_multisig_data(4, 3, 0), 3),
+    ] {
+        let (pda_proposal, pda_transaction) = common::approve_config_change(
+            &mut svm,
+            &[&second_admin, &third_admin],
+            program_id,
+            pda_multisig,
+            data.clone(),
+            proposal_seed,
+        );
+
+        let result = common::update_multisig(
+            &mut svm,
+            &second_admin,
+            program_id,
+            pda_multisig,
+            pda_proposal,
+            pda_transaction,
+            data,
+        );
+        assert_eq!(result.unwrap_err().err, unreachable);
+    }
+}
+
+#[test]
+pub fn test_time_lock_delays_execution() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+
+    let data = common::update_multisig_data(6, 3600, 0);
+    let (pda_multisig, pda_proposal, pda_transaction) = approved_proposal(
+        &mut svm,
+        &fee_payer,
+        &[&second_admin, &third_admin],
+        program_id,
+        common::config_payload(program_id, data.clone()),
+    );
+
+    let result = common::update_multisig(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_transaction,
+        data,
+    );
+    assert!(result.is_ok());
+
+    let multisig_account = svm.get_account(&pda_multisig).unwrap();
+    let multisig_state: &MultisigState =
+        bytemuck::from_bytes(&multisig_account.data[..MultisigState::LEN]);
+    assert_eq!(multisig_state.time_lock, 3600);
+
+    // Any approved proposal now waits an hour before it can be executed
+    let data = common::update_multisig_data(2, 500, 0);
+    let pda_transaction = common::create_transaction(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        &[common::config_payload(program_id, data.clone())],
+    );
+    let (pda_proposal, proposal_bump) = common::create_proposal_with_seed(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+        1,
+    );
+    common::activate_proposal(&mut svm, &second_admin, program_id, pda_multisig, pda_proposal);
+
+    for admin in [&second_admin, &third_admin] {
+        common::vote(
+            &mut svm,
+            admin,
+            program_id,
+            pda_multisig,
+            multisig_state.bump,
+            pda_proposal,
+            proposal_bump,
+            1,
+        );
+    }
+
+    let result = common::update_multisig(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_transaction,
+        data.clone(),
+    );
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::TimeLockActive as u32)
+        )
+    );
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp += 3600;
+    svm.set_sysvar::<Clock>(&clock);
+    svm.expire_blockhash();
+
+    let result = common::update_multisig(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        pda_transaction,
+        data,
+    );
+    assert!(result.is_ok());
+
+    let multisig_account = svm.get_account(&pda_multisig).unwrap();
+    let multisig_state: &MultisigState =
+        bytemuck::from_bytes(&multisig_account.data[..MultisigState::LEN]);
+    assert_eq!(multisig_state.spending_limit, 500);
+}
@@ -1,8 +1,33 @@
-use pinocchio_multisig::{helper::StateDefinition, state::ProposalState};
-use solana_sdk::{signature::Keypair, signer::Signer};
+use pinocchio_multisig::{
+    error::MultisigError,
+    helper::StateDefinition,
+    state::{MemberState, Permission, ProposalState, ProposalStatus},
+};
+use solana_sdk::{
+    clock::Clock,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    transaction::TransactionError,
+};
 
 mod common;
 
+/// Creates a multisig with two extra members holding most of the weight, so the `voters`
+/// can change their votes without deciding the proposal on their own
+fn create_undecided_multisig(
+    svm: &mut litesvm::LiteSVM,
+    fee_payer: &Keypair,
+    program_id: Pubkey,
+    voters: Vec<Pubkey>,
+) -> (Pubkey, u8) {
+    let mut members = voters;
+    members.extend([Pubkey::new_unique(), Pubkey::new_unique()]);
+
+    common::create_weighted_multisig(svm, fee_payer, program_id, members, &[1, 1, 2, 2], &[], 3)
+}
+
 #[test]
 pub fn test_first_vote_yes() {
     let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
@@ -14,8 +39,16 @@ pub fn test_first_vote_yes() {
     let (pda_multisig, multisig_bump) =
         common::create_multisig(&mut svm, &fee_payer, program_id, admins);
 
-    let (pda_proposal, proposal_bump) =
-        common::create_proposal(&mut svm, &second_admin, program_id, pda_multisig);
+    let pda_transaction =
+        common::create_placeholder_transaction(&mut svm, &second_admin, program_id, pda_multisig);
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+    );
+    common::activate_proposal(&mut svm, &second_admin, program_id, pda_multisig, pda_proposal);
 
     // First vote: Yes
     common::vote(
@@ -51,8 +84,16 @@ pub fn test_first_vote_no() {
     let (pda_multisig, multisig_bump) =
         common::create_multisig(&mut svm, &fee_payer, program_id, admins);
 
-    let (pda_proposal, proposal_bump) =
-        common::create_proposal(&mut svm, &second_admin, program_id, pda_multisig);
+    let pda_transaction =
+        common::create_placeholder_transaction(&mut svm, &second_admin, program_id, pda_multisig);
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+    );
+    common::activate_proposal(&mut svm, &second_admin, program_id, pda_multisig, pda_proposal);
 
     // First vote: No
     common::vote(
@@ -87,10 +128,18 @@ pub fn test_change_vote_from_yes_to_no() {
     let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
 
     let (pda_multisig, multisig_bump) =
-        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+        create_undecided_multisig(&mut svm, &fee_payer, program_id, admins);
 
-    let (pda_proposal, proposal_bump) =
-        common::create_proposal(&mut svm, &second_admin, program_id, pda_multisig);
+    let pda_transaction =
+        common::create_placeholder_transaction(&mut svm, &second_admin, program_id, pda_multisig);
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+    );
+    common::activate_proposal(&mut svm, &second_admin, program_id, pda_multisig, pda_proposal);
 
     // First vote: Yes
     common::vote(
@@ -137,10 +186,18 @@ pub fn test_change_vote_from_no_to_yes() {
     let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
 
     let (pda_multisig, multisig_bump) =
-        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+        create_undecided_multisig(&mut svm, &fee_payer, program_id, admins);
 
-    let (pda_proposal, proposal_bump) =
-        common::create_proposal(&mut svm, &second_admin, program_id, pda_multisig);
+    let pda_transaction =
+        common::create_placeholder_transaction(&mut svm, &second_admin, program_id, pda_multisig);
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+    );
+    common::activate_proposal(&mut svm, &second_admin, program_id, pda_multisig, pda_proposal);
 
     // First vote: No
     common::vote(
@@ -187,10 +244,18 @@ pub fn test_multiple_votes_yes_then_no() {
     let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
 
     let (pda_multisig, multisig_bump) =
-        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+        create_undecided_multisig(&mut svm, &fee_payer, program_id, admins);
 
-    let (pda_proposal, proposal_bump) =
-        common::create_proposal(&mut svm, &second_admin, program_id, pda_multisig);
+    let pda_transaction =
+        common::create_placeholder_transaction(&mut svm, &second_admin, program_id, pda_multisig);
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+    );
+    common::activate_proposal(&mut svm, &second_admin, program_id, pda_multisig, pda_proposal);
 
     // Vote Yes multiple times (should change to No after first)
     common::vote(
@@ -259,10 +324,18 @@ pub fn test_multiple_votes_no_then_yes() {
     svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
     let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
     let (pda_multisig, multisig_bump) =
-        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+        create_undecided_multisig(&mut svm, &fee_payer, program_id, admins);
 
-    let (pda_proposal, proposal_bump) =
-        common::create_proposal(&mut svm, &second_admin, program_id, pda_multisig);
+    let pda_transaction =
+        common::create_placeholder_transaction(&mut svm, &second_admin, program_id, pda_multisig);
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+    );
+    common::activate_proposal(&mut svm, &second_admin, program_id, pda_multisig, pda_proposal);
 
     // Vote No multiple times (should change to Yes after first)
     common::vote(
@@ -321,10 +394,18 @@ pub fn test_vote_alternating_pattern() {
     let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
 
     let (pda_multisig, multisig_bump) =
-        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+        create_undecided_multisig(&mut svm, &fee_payer, program_id, admins);
 
-    let (pda_proposal, proposal_bump) =
-        common::create_proposal(&mut svm, &second_admin, program_id, pda_multisig);
+    let pda_transaction =
+        common::create_placeholder_transaction(&mut svm, &second_admin, program_id, pda_multisig);
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+    );
+    common::activate_proposal(&mut svm, &second_admin, program_id, pda_multisig, pda_proposal);
 
     // Vote Yes
     common::vote(
@@ -405,3 +486,451 @@ pub fn test_vote_alternating_pattern() {
     assert_eq!(proposal_votes.len(), 1);
     assert_eq!(proposal_votes[0], second_admin.pubkey().as_ref());
 }
+
+#[test]
+pub fn test_vote_after_expiry_fails() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    let (pda_multisig, _multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let pda_transaction =
+        common::create_placeholder_transaction(&mut svm, &second_admin, program_id, pda_multisig);
+    let (pda_proposal, _proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+    );
+    common::activate_proposal(&mut svm, &second_admin, program_id, pda_multisig, pda_proposal);
+
+    // Move the clock past the proposal expiry
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = 1_000_000;
+    svm.set_sysvar::<Clock>(&clock);
+
+    let vote_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(second_admin.pubkey(), true),
+            AccountMeta::new(pda_multisig, false),
+            AccountMeta::new(pda_proposal, false),
+        ],
+        data: vec![3, 0, 0, 1],
+    };
+    let result = common::build_and_send_transaction(&mut svm, &second_admin, vec![vote_ix]);
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::ProposalExpired as u32)
+        )
+    );
+
+    // The vote is not recorded
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let proposal_data = &proposal_account.data;
+    let proposal_state = ProposalState::from_bytes(proposal_data).unwrap();
+    assert_eq!(proposal_state.status, ProposalStatus::Active);
+    assert_eq!(proposal_state.yes_votes, 0);
+    assert_eq!(proposal_data.len(), ProposalState::LEN);
+
+    // Closing moves the expired proposal to `Failed` and reclaims it
+    let result = common::close_proposal(
+        &mut svm,
+        &third_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        second_admin.pubkey(),
+        vec![],
+    );
+    assert!(result.is_ok());
+    assert!(svm.get_account(&pda_proposal).map_or(true, |account| account.lamports == 0));
+}
+
+#[test]
+pub fn test_vote_on_draft_proposal_fails() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    let (pda_multisig, _multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let pda_transaction =
+        common::create_placeholder_transaction(&mut svm, &second_admin, program_id, pda_multisig);
+    let (pda_proposal, _proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+    );
+
+    // The proposal was never activated
+    let vote_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(third_admin.pubkey(), true),
+            AccountMeta::new(pda_multisig, false),
+            AccountMeta::new(pda_proposal, false),
+        ],
+        data: vec![3, 0, 0, 1],
+    };
+    let result = common::build_and_send_transaction(&mut svm, &third_admin, vec![vote_ix]);
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::ProposalNotActive as u32)
+        )
+    );
+
+    // Any admin can activate it, after which voting works
+    common::activate_proposal(&mut svm, &third_admin, program_id, pda_multisig, pda_proposal);
+
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let proposal_state = ProposalState::from_bytes(&proposal_account.data).unwrap();
+    assert_eq!(proposal_state.status, ProposalStatus::Active);
+}
+
+#[test]
+pub fn test_weighted_votes() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    let fourth_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    svm.airdrop(&fourth_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![
+        second_admin.pubkey(),
+        third_admin.pubkey(),
+        fourth_admin.pubkey(),
+    ];
+
+    // Two votes are enough by count, but they also need a summed weight of 6
+    let (pda_multisig, multisig_bump) = common::create_weighted_multisig(
+        &mut svm,
+        &fee_payer,
+        program_id,
+        admins,
+        &[1, 1, 5],
+        &[],
+        6,
+    );
+
+    let pda_transaction =
+        common::create_placeholder_transaction(&mut svm, &second_admin, program_id, pda_multisig);
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+    );
+    common::activate_proposal(&mut svm, &second_admin, program_id, pda_multisig, pda_proposal);
+
+    for voter in [&second_admin, &third_admin] {
+        common::vote(
+            &mut svm,
+            voter,
+            program_id,
+            pda_multisig,
+            multisig_bump,
+            pda_proposal,
+            proposal_bump,
+            1,
+        );
+    }
+
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let proposal_state = ProposalState::from_bytes(&proposal_account.data).unwrap();
+    assert_eq!(proposal_state.yes_votes, 2);
+    assert_eq!(proposal_state.yes_weight, 2);
+
+    // Enough votes but not enough weight, the proposal is still open
+    let result = common::close_proposal(
+        &mut svm,
+        &third_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        second_admin.pubkey(),
+        vec![],
+    );
+    assert!(result.is_err());
+
+    common::vote(
+        &mut svm,
+        &fourth_admin,
+        program_id,
+        pda_multisig,
+        multisig_bump,
+        pda_proposal,
+        proposal_bump,
+        1,
+    );
+
+    let result = common::close_proposal(
+        &mut svm,
+        &third_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        second_admin.pubkey(),
+        vec![],
+    );
+    assert!(result.is_ok());
+
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let proposal_state = ProposalState::from_bytes(&proposal_account.data).unwrap();
+    assert_eq!(proposal_state.yes_weight, 7);
+    assert_eq!(proposal_state.status, ProposalStatus::Succeeded);
+}
+
+#[test]
+pub fn test_vote_without_permission_fails() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey(), Pubkey::new_unique()];
+
+    // The second admin can propose but not vote, the two others can still reach the threshold
+    let proposer = Permission::Initiate as u8;
+    let (pda_multisig, _multisig_bump) = common::create_weighted_multisig(
+        &mut svm,
+        &fee_payer,
+        program_id,
+        admins,
+        &[],
+        &[proposer, MemberState::ALL_PERMISSIONS, MemberState::ALL_PERMISSIONS],
+        0,
+    );
+
+    let pda_transaction =
+        common::create_placeholder_transaction(&mut svm, &second_admin, program_id, pda_multisig);
+    let (pda_proposal, _proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+    );
+    common::activate_proposal(&mut svm, &second_admin, program_id, pda_multisig, pda_proposal);
+
+    let vote_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(second_admin.pubkey(), true),
+            AccountMeta::new(pda_multisig, false),
+            AccountMeta::new(pda_proposal, false),
+        ],
+        data: vec![3, 0, 0, 1],
+    };
+    let result = common::build_and_send_transaction(&mut svm, &second_admin, vec![vote_ix]);
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::MissingPermission as u32)
+        )
+    );
+}
+
+#[test]
+pub fn test_abstain_vote() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    let (pda_multisig, multisig_bump) =
+        create_undecided_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let pda_transaction =
+        common::create_placeholder_transaction(&mut svm, &second_admin, program_id, pda_multisig);
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+    );
+    common::activate_proposal(&mut svm, &second_admin, program_id, pda_multisig, pda_proposal);
+
+    // Abstain, then the other admin votes yes
+    common::vote(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        multisig_bump,
+        pda_proposal,
+        proposal_bump,
+        2,
+    );
+    common::vote(
+        &mut svm,
+        &third_admin,
+        program_id,
+        pda_multisig,
+        multisig_bump,
+        pda_proposal,
+        proposal_bump,
+        1,
+    );
+
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let proposal_data = &proposal_account.data;
+    let proposal_state = ProposalState::from_bytes(proposal_data).unwrap();
+    assert_eq!(proposal_state.yes_votes, 1);
+    assert_eq!(proposal_state.no_votes, 0);
+    assert_eq!(proposal_state.abstain_votes, 1);
+    assert_eq!(proposal_state.abstain_weight, 1);
+
+    let proposal_votes_data = &proposal_data[ProposalState::LEN..];
+    let proposal_votes = proposal_votes_data.chunks_exact(32).collect::<Vec<&[u8]>>();
+    assert_eq!(proposal_votes[0], third_admin.pubkey().as_ref());
+    assert_eq!(proposal_votes[1], second_admin.pubkey().as_ref());
+
+    // Abstaining doesn't count toward approval
+    let result = common::close_proposal(
+        &mut svm,
+        &third_admin,
+        program_id,
+        pda_multisig,
+        pda_proposal,
+        second_admin.pubkey(),
+        vec![third_admin.pubkey()],
+    );
+    assert!(result.is_err());
+
+    // Values other than yes, no and abstain are rejected
+    let vote_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(second_admin.pubkey(), true),
+            AccountMeta::new(pda_multisig, false),
+            AccountMeta::new(pda_proposal, false),
+        ],
+        data: vec![3, 0, 0, 3],
+    };
+    let result = common::build_and_send_transaction(&mut svm, &second_admin, vec![vote_ix]);
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::InvalidVote as u32)
+        )
+    );
+}
+
+#[test]
+pub fn test_vote_reaching_threshold_succeeds_proposal() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    let (pda_multisig, multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let pda_transaction =
+        common::create_placeholder_transaction(&mut svm, &second_admin, program_id, pda_multisig);
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+    );
+    common::activate_proposal(&mut svm, &second_admin, program_id, pda_multisig, pda_proposal);
+
+    for voter in [&second_admin, &third_admin] {
+        common::vote(
+            &mut svm,
+            voter,
+            program_id,
+            pda_multisig,
+            multisig_bump,
+            pda_proposal,
+            proposal_bump,
+            1,
+        );
+    }
+
+    // No close needed to learn the outcome
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let proposal_state = ProposalState::from_bytes(&proposal_account.data).unwrap();
+    assert_eq!(proposal_state.yes_votes, 2);
+    assert_eq!(proposal_state.status, ProposalStatus::Succeeded);
+}
+
+#[test]
+pub fn test_vote_making_threshold_unreachable_fails_proposal() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    let (pda_multisig, multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let pda_transaction =
+        common::create_placeholder_transaction(&mut svm, &second_admin, program_id, pda_multisig);
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        pda_transaction,
+    );
+    common::activate_proposal(&mut svm, &second_admin, program_id, pda_multisig, pda_proposal);
+
+    // Both admins are needed, a single no vote decides the proposal
+    common::vote(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        multisig_bump,
+        pda_proposal,
+        proposal_bump,
+        0,
+    );
+
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let proposal_state = ProposalState::from_bytes(&proposal_account.data).unwrap();
+    assert_eq!(proposal_state.no_votes, 1);
+    assert_eq!(proposal_state.status, ProposalStatus::Failed);
+
+    let vote_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(third_admin.pubkey(), true),
+            AccountMeta::new(pda_multisig, false),
+            AccountMeta::new(pda_proposal, false),
+        ],
+        data: vec![3, multisig_bump, proposal_bump, 1],
+    };
+    let result = common::build_and_send_transaction(&mut svm, &third_admin, vec![vote_ix]);
+    assert_eq!(
+        result.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::ProposalNotActive as u32)
+        )
+    );
+}